@@ -0,0 +1,324 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::game::attack::BodyRadius;
+use crate::game::building_spawning::PlaceBuildingEvent;
+use crate::game::buildings::{count_owned_buildings, Building, Castle, BUILDING_HALF_SIZE};
+use crate::game::castle_fight_ldtk::LevelBounds;
+use crate::game::construction::UnderConstruction;
+use crate::game::economy::Treasuries;
+use crate::game::grid_traits::{SnapToGrid, GRID_SIZE};
+use crate::game::spawning::BlueprintId;
+use crate::game::teams::Team;
+use crate::game::waypoints::Waypoint;
+use crate::load_game::load_factions::BuildingBlueprint;
+use crate::resources::{AiDifficulty, Controller, TeamSettings};
+
+/*
+Computer-controlled opponents. Every team controlled by the computer gets an AI,
+which places buildings from its faction around its castle through the same path as the player.
+*/
+
+// --- Plugin ---
+
+pub struct AiPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for AiPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AiControllers>()
+            .add_systems(OnEnter(self.state.clone()), setup_ai_controllers)
+            .add_systems(
+                Update,
+                ai_place_buildings.run_if(in_state(self.state.clone())),
+            );
+    }
+}
+
+// --- Constants ---
+
+/// How many rings of building slots around the castle the AI will consider.
+const MAX_BUILD_RINGS: i32 = 6;
+/// Units walking down a lane take up this much room on each side of the line between its waypoints.
+const LANE_HALF_WIDTH: f32 = 32.;
+
+// --- Enums ---
+
+/// How the AI decides which building to place next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildStrategy {
+    /// Always place the cheapest building it can afford.
+    Cheapest,
+    /// Place the building it owns the fewest of, to get a mix of units.
+    Balanced,
+    /// Save up for the most expensive building it owns the fewest of.
+    Saving,
+}
+
+// --- Resources ---
+
+/// How a computer-controlled team plays.
+#[derive(Clone, Debug)]
+pub struct AiProfile {
+    /// Seconds between each attempt to place a building.
+    pub build_interval: f32,
+    pub strategy: BuildStrategy,
+    /// Gold the AI will keep in its treasury, without spending it.
+    pub gold_reserve: u32,
+}
+
+impl AiProfile {
+    pub fn from_difficulty(difficulty: AiDifficulty) -> AiProfile {
+        match difficulty {
+            AiDifficulty::Easy => AiProfile {
+                build_interval: 12.,
+                strategy: BuildStrategy::Cheapest,
+                gold_reserve: 50,
+            },
+            AiDifficulty::Normal => AiProfile {
+                build_interval: 7.,
+                strategy: BuildStrategy::Balanced,
+                gold_reserve: 0,
+            },
+            AiDifficulty::Hard => AiProfile {
+                build_interval: 4.,
+                strategy: BuildStrategy::Saving,
+                gold_reserve: 0,
+            },
+        }
+    }
+}
+
+struct AiController {
+    profile: AiProfile,
+    build_timer: Timer,
+}
+
+/// The AI of every team controlled by the computer.
+#[derive(Resource, Default)]
+struct AiControllers(HashMap<Team, AiController>);
+
+// --- Systems ---
+
+fn setup_ai_controllers(
+    mut ai_controllers: ResMut<AiControllers>,
+    team_settings: Res<TeamSettings>,
+) {
+    ai_controllers.0.clear();
+    for (team, team_setting) in team_settings.0.iter() {
+        if let Controller::Ai(difficulty) = team_setting.controller {
+            let profile = AiProfile::from_difficulty(difficulty);
+            let build_timer = Timer::from_seconds(profile.build_interval, TimerMode::Repeating);
+            ai_controllers.0.insert(
+                *team,
+                AiController {
+                    profile,
+                    build_timer,
+                },
+            );
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+#[allow(clippy::too_many_arguments)]
+fn ai_place_buildings(
+    mut ai_controllers: ResMut<AiControllers>,
+    team_settings: Res<TeamSettings>,
+    treasuries: Res<Treasuries>,
+    castle_query: Query<(&Transform, &Team), With<Castle>>,
+    building_query: Query<
+        (
+            &Transform,
+            &Team,
+            Option<&BlueprintId>,
+            &BodyRadius,
+            Has<UnderConstruction>,
+        ),
+        With<Building>,
+    >,
+    waypoint_query: Query<(&Transform, &Waypoint)>,
+    level_bounds: Res<LevelBounds>,
+    mut ev_place_building: EventWriter<PlaceBuildingEvent>,
+    time: Res<Time>,
+) {
+    // Wait for the level, so buildings aren't placed outside of it.
+    let Some(level_rect) = level_bounds.0 else {
+        return;
+    };
+
+    for (team, ai_controller) in ai_controllers.0.iter_mut() {
+        if !ai_controller.build_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let Some(faction) = team_settings.faction(*team) else {
+            continue;
+        };
+
+        let owned_buildings = count_owned_buildings(
+            *team,
+            building_query
+                .iter()
+                // Buildings under construction don't count towards requirements.
+                .filter(|(_, _, _, _, is_under_construction)| !is_under_construction)
+                .filter_map(|(_, building_team, opt_blueprint_id, _, _)| {
+                    Some((building_team, opt_blueprint_id?))
+                }),
+        );
+
+        let spendable_gold = treasuries
+            .gold(*team)
+            .saturating_sub(ai_controller.profile.gold_reserve);
+        let Some(building_blueprint) = choose_building(
+            // Only consider the buildings that can be placed, and that the team has the required buildings for.
+            faction.buildings.values().filter(|building| {
+                building.buildable && building.missing_requirement(&owned_buildings).is_none()
+            }),
+            &owned_buildings,
+            spendable_gold,
+            ai_controller.profile.strategy,
+        ) else {
+            continue;
+        };
+
+        // Wait for more gold, if the chosen building can't be afforded yet.
+        if !treasuries.can_afford(*team, building_blueprint.cost) {
+            continue;
+        }
+
+        let Some((castle_transform, _)) = castle_query
+            .iter()
+            .find(|(_, castle_team)| *castle_team == team)
+        else {
+            continue;
+        };
+
+        // Buildings and castles have square footprints, as wide as their bodies.
+        let occupied: Vec<(Vec2, f32)> = building_query
+            .iter()
+            .map(|(transform, _, _, body_radius, _)| {
+                (transform.translation.truncate(), body_radius.0)
+            })
+            .collect();
+        let lanes: Vec<(Vec2, Vec2)> = waypoint_query
+            .iter()
+            .filter_map(|(transform, waypoint)| {
+                let (next_transform, _) = waypoint_query.get(waypoint.next_waypoint?).ok()?;
+                Some((
+                    transform.translation.truncate(),
+                    next_transform.translation.truncate(),
+                ))
+            })
+            .collect();
+
+        if let Some(position) =
+            find_building_position(castle_transform.translation.truncate(), |position| {
+                is_area_free(position, &occupied)
+                    && is_inside_level(position, level_rect)
+                    && !is_on_lane(position, &lanes)
+            })
+        {
+            ev_place_building.send(PlaceBuildingEvent {
+                building_blueprint: building_blueprint.clone(),
+                team: *team,
+                position,
+                ghost: None,
+            });
+        }
+    }
+}
+
+// --- Helper functions ---
+
+/// Picks the next building to place based on the strategy.
+/// Returns `None` if there is nothing worth placing right now.
+fn choose_building<'a>(
+    buildings: impl Iterator<Item = &'a BuildingBlueprint>,
+    owned_buildings: &HashMap<&str, usize>,
+    spendable_gold: u32,
+    strategy: BuildStrategy,
+) -> Option<&'a BuildingBlueprint> {
+    let owned = |building: &BuildingBlueprint| -> usize {
+        owned_buildings
+            .get(building.id.as_str())
+            .copied()
+            .unwrap_or(0)
+    };
+
+    match strategy {
+        BuildStrategy::Cheapest => buildings
+            .filter(|building| building.cost <= spendable_gold)
+            .min_by_key(|building| (building.cost, building.id.clone())),
+        BuildStrategy::Balanced => buildings
+            .filter(|building| building.cost <= spendable_gold)
+            .min_by_key(|building| (owned(building), building.cost, building.id.clone())),
+        BuildStrategy::Saving => buildings.min_by_key(|building| {
+            (
+                owned(building),
+                u32::MAX - building.cost,
+                building.id.clone(),
+            )
+        }),
+    }
+}
+
+/// Finds the grid position closest to the castle, where a building fits.
+fn find_building_position(castle_position: Vec2, fits: impl Fn(Vec2) -> bool) -> Option<Vec2> {
+    let slot_size = BUILDING_HALF_SIZE * 2.;
+    for ring in 1..=MAX_BUILD_RINGS {
+        let mut candidates: Vec<Vec2> = vec![];
+        for x in -ring..=ring {
+            for y in -ring..=ring {
+                // Only the outer edge of the ring, as the inside was checked in earlier rings.
+                if x.abs() != ring && y.abs() != ring {
+                    continue;
+                }
+                let position = (castle_position + Vec2::new(x as f32, y as f32) * slot_size)
+                    .snap_to_grid(GRID_SIZE);
+                candidates.push(position);
+            }
+        }
+        candidates.sort_by(|a, b| {
+            a.distance_squared(castle_position)
+                .total_cmp(&b.distance_squared(castle_position))
+        });
+
+        if let Some(position) = candidates.into_iter().find(|position| fits(*position)) {
+            return Some(position);
+        }
+    }
+    None
+}
+
+fn is_area_free(position: Vec2, occupied: &[(Vec2, f32)]) -> bool {
+    occupied.iter().all(|(other_position, other_half_size)| {
+        let min_distance = BUILDING_HALF_SIZE + other_half_size;
+        let distance = (position - *other_position).abs();
+        distance.x >= min_distance || distance.y >= min_distance
+    })
+}
+
+/// The whole footprint of the building must be inside the level.
+fn is_inside_level(position: Vec2, level_rect: Rect) -> bool {
+    let footprint = Rect::from_center_half_size(position, Vec2::splat(BUILDING_HALF_SIZE));
+    level_rect.contains(footprint.min) && level_rect.contains(footprint.max)
+}
+
+/// If the footprint of the building would be in the way of units walking down one of the lanes.
+fn is_on_lane(position: Vec2, lanes: &[(Vec2, Vec2)]) -> bool {
+    lanes.iter().any(|(start, end)| {
+        distance_to_segment(position, *start, *end) < BUILDING_HALF_SIZE + LANE_HALF_WIDTH
+    })
+}
+
+fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared == 0. {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(segment) / length_squared).clamp(0., 1.);
+    point.distance(start + segment * t)
+}
@@ -1,5 +1,12 @@
+use crate::game::auras::AuraBuffs;
+use crate::game::construction::UnderConstruction;
+use crate::game::damage::{DamageEvent, DamageKind, DamageSource, DamageType};
 use crate::game::health::Health;
-use crate::game::vision::InVision;
+use crate::game::projectiles::{spawn_projectile, Projectile, ProjectileLauncher};
+use crate::game::splash::{SplashDamage, SplashEvent};
+use crate::game::status_effects::{OnHitEffects, StatusEffectEvent, StatusEffects};
+use crate::game::teams::Team;
+use crate::resources::TeamSettings;
 use bevy::prelude::*;
 use std::time::Duration;
 
@@ -11,79 +18,199 @@ pub struct AttackPlugin<S: States> {
 
 impl<S: States> Plugin for AttackPlugin<S> {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            PreUpdate,
-            find_attack_target.run_if(in_state(self.state.clone())),
-        )
-        .add_systems(Update, attack_target.run_if(in_state(self.state.clone())));
+        app.add_systems(Update, attack_target.run_if(in_state(self.state.clone())));
     }
 }
 
+// --- Constants ---
+
+/// The fewest attacks per second, however much the attack speed is lowered by auras.
+const MIN_ATTACK_SPEED: f32 = 0.1;
+
 // --- Components ---
 
 #[derive(Component, Debug)]
 pub struct AttackStats {
     pub damage: i32,
+    pub damage_type: DamageType,
     pub attack_speed: f32,
     /// Used to check if the attack target is within striking range.
+    /// The range is measured from the edge of the attacker's body to the edge of the target's body.
     pub attack_range: f32,
     pub time_till_next_attack: Timer,
 }
 
-#[derive(Component)]
-pub struct AttackTarget(pub Entity); // TODO: Make it contain a list of targets.
-                                     // The reason for the above to do is that when a target is destroyed or out of sight
-                                     // new potential targets that are already inside the sensor collider range will not be
-                                     // be used as new targets. We therefore need to store all targets within the vision range.
-                                     // NOTE: Perhaps we could have the list on another component (AttackTargetsInVision)
-                                     // This component would always create a new AttackTarget component with the next target, if none exist.
-
-// --- Systems ---
-
-#[allow(clippy::type_complexity)]
-fn find_attack_target(
-    mut commands: Commands,
-    query: Query<(Entity, &InVision), (With<AttackStats>, Without<AttackTarget>)>,
-) {
-    for (entity, in_vision) in query.iter() {
-        if !in_vision.enemies.is_empty() {
-            let target = in_vision.enemies[0];
-            commands.entity(entity).insert(AttackTarget(target));
+impl AttackStats {
+    /// The first attack is ready right away.
+    pub fn new(
+        damage: i32,
+        damage_type: DamageType,
+        attack_speed: f32,
+        attack_range: f32,
+    ) -> AttackStats {
+        AttackStats {
+            damage,
+            damage_type,
+            attack_speed,
+            attack_range,
+            time_till_next_attack: Timer::new(Duration::from_secs(0), TimerMode::Once),
         }
     }
+
+    pub fn in_range(
+        &self,
+        (position, opt_radius): (Vec2, Option<&BodyRadius>),
+        (target_position, opt_target_radius): (Vec2, Option<&BodyRadius>),
+    ) -> bool {
+        let radius = opt_radius.map_or(0., |body_radius| body_radius.0);
+        let target_radius = opt_target_radius.map_or(0., |body_radius| body_radius.0);
+        let edge_distance = position.distance(target_position) - radius - target_radius;
+        edge_distance <= self.attack_range
+    }
 }
 
+/// The radius of an entity's body, matching the size of its collider.
+/// Used to measure distances from edge to edge instead of from center to center.
+#[derive(Component, Reflect)]
+pub struct BodyRadius(pub f32);
+
+/// The entity currently being attacked. It is chosen and replaced by the targeting systems.
+#[derive(Component)]
+pub struct AttackTarget(pub Entity);
+
+// --- Systems ---
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn attack_target(
     mut commands: Commands,
-    mut attacker_query: Query<(Entity, &mut AttackStats, &AttackTarget)>,
-    mut defender_query: Query<&mut Health>,
+    mut attacker_query: Query<
+        (
+            Entity,
+            &mut AttackStats,
+            &AttackTarget,
+            &Transform,
+            Option<&BodyRadius>,
+            &Team,
+            Option<&ProjectileLauncher>,
+            Option<&SplashDamage>,
+            Option<&OnHitEffects>,
+            Option<&StatusEffects>,
+            Option<&AuraBuffs>,
+        ),
+        Without<UnderConstruction>,
+    >,
+    defender_query: Query<(&Transform, Option<&BodyRadius>), With<Health>>,
+    team_settings: Res<TeamSettings>,
+    mut ev_damage: EventWriter<DamageEvent>,
+    mut ev_splash: EventWriter<SplashEvent>,
+    mut ev_status_effect: EventWriter<StatusEffectEvent>,
     time: Res<Time>,
 ) {
-    for (entity, mut attack_stats, target) in attacker_query.iter_mut() {
+    for (
+        entity,
+        mut attack_stats,
+        target,
+        transform,
+        opt_body_radius,
+        team,
+        opt_projectile_launcher,
+        opt_splash_damage,
+        opt_on_hit_effects,
+        opt_status_effects,
+        opt_aura_buffs,
+    ) in attacker_query.iter_mut()
+    {
+        // Stunned attackers neither attack nor recover from their last attack.
+        if opt_status_effects.is_some_and(|status_effects| status_effects.is_stunned()) {
+            continue;
+        }
+
         // Don't attack, if attack cooldown hasn't finished.
         if !attack_stats.time_till_next_attack.finished() {
             // tick the timer
             attack_stats.time_till_next_attack.tick(time.delta());
 
-            return;
+            continue;
         }
 
-        if let Ok(mut health) = defender_query.get_mut(target.0) {
-            // TODO: Make a more intricate damage calculation.
-            health.health -= attack_stats.damage;
-            info!("{:?} damage taken!", attack_stats.damage);
+        if let Ok((target_transform, opt_target_body_radius)) = defender_query.get(target.0) {
+            // Wait until the target is within range. The attack is ready as soon as it is.
+            if !attack_stats.in_range(
+                (transform.translation.truncate(), opt_body_radius),
+                (
+                    target_transform.translation.truncate(),
+                    opt_target_body_radius,
+                ),
+            ) {
+                continue;
+            }
+
+            let source = DamageSource {
+                entity,
+                team: *team,
+            };
+
+            if let Some(projectile_launcher) = opt_projectile_launcher {
+                // Ranged attackers deal their damage, when the projectile hits.
+                let origin = transform.translation.truncate();
+                spawn_projectile(
+                    &mut commands,
+                    projectile_launcher,
+                    Projectile {
+                        source,
+                        target: target.0,
+                        damage: attack_stats.damage,
+                        damage_type: attack_stats.damage_type,
+                        speed: projectile_launcher.speed,
+                        homing: projectile_launcher.homing,
+                        arc_height: projectile_launcher.arc_height,
+                        origin,
+                        destination: target_transform.translation.truncate(),
+                        ground_position: origin,
+                        splash: opt_splash_damage.copied(),
+                        on_hit_effects: opt_on_hit_effects.cloned(),
+                    },
+                    team_settings.color(*team),
+                );
+            } else {
+                ev_damage.send(DamageEvent {
+                    source: Some(source),
+                    target: target.0,
+                    amount: attack_stats.damage,
+                    kind: DamageKind::Attack(attack_stats.damage_type),
+                });
+
+                if let Some(on_hit_effects) = opt_on_hit_effects {
+                    ev_status_effect.send(StatusEffectEvent {
+                        target: target.0,
+                        source,
+                        effects: on_hit_effects.0.clone(),
+                    });
+                }
+
+                if let Some(splash_damage) = opt_splash_damage {
+                    ev_splash.send(SplashEvent {
+                        position: target_transform.translation.truncate(),
+                        source,
+                        damage: attack_stats.damage,
+                        damage_type: attack_stats.damage_type,
+                        splash: *splash_damage,
+                        primary_target: Some(target.0),
+                    });
+                }
+            }
 
             //Set a new timer
-            attack_stats.time_till_next_attack = Timer::new(
-                Duration::from_secs(1 / attack_stats.attack_speed as u64),
-                TimerMode::Once,
-            );
+            let attack_speed = (attack_stats.attack_speed
+                * opt_aura_buffs.map_or(1., |aura_buffs| aura_buffs.attack_speed_multiplier()))
+            .max(MIN_ATTACK_SPEED);
+            attack_stats.time_till_next_attack =
+                Timer::new(Duration::from_secs_f32(1. / attack_speed), TimerMode::Once);
         } else {
             // If the target has no health component,
             // it probably died, so lets remove the attack target.
+            // A new target is picked from the attack candidates by the targeting systems.
             commands.entity(entity).remove::<AttackTarget>();
-
-            // TODO: When fixing targeting, remove the target from the list, if it exist. Remove the component if there are no targets left.
         }
     }
 }
@@ -0,0 +1,246 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use bevy::utils::HashMap;
+use bevy_spatial::SpatialAccess;
+use serde::Deserialize;
+
+use crate::game::healing::HealEvent;
+use crate::game::health::Health;
+use crate::game::teams::Team;
+use crate::game::vision::TeamEntityTree;
+
+/*
+Auras are buffs that buildings and units give to friendlies around them, including themselves.
+The buffs of all auras reaching an entity are combined into its `AuraBuffs`,
+which the attack, damage and healing systems read.
+*/
+
+// --- Plugin ---
+
+pub struct AuraPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for AuraPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                update_aura_buffs.run_if(on_timer(Duration::from_millis(200))),
+                regenerate_health.run_if(on_timer(Duration::from_secs(1))),
+            )
+                .run_if(in_state(self.state.clone())),
+        );
+    }
+}
+
+// --- Enums ---
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Reflect)]
+pub enum AuraEffect {
+    /// Increases attack speed by a fraction, so 0.2 is +20% attack speed.
+    AttackSpeed(f32),
+    /// Adds armor.
+    Armor(i32),
+    /// Restores health every second.
+    HealthRegen(i32),
+}
+
+/// How auras of the same type reaching the same entity are combined.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default, Reflect)]
+pub enum AuraStacking {
+    /// Only the strongest aura of the type counts.
+    #[default]
+    Strongest,
+    /// The aura adds to the other auras of the type.
+    Additive,
+}
+
+// --- Types ---
+
+#[derive(Deserialize, Clone, PartialEq, Debug, Reflect)]
+pub struct Aura {
+    pub effect: AuraEffect,
+    pub radius: f32,
+    #[serde(default)]
+    pub stacking: AuraStacking,
+}
+
+// --- Components ---
+
+/// The auras an entity gives to friendlies within their radius.
+#[derive(Component, Clone, Debug, Reflect)]
+pub struct Auras(pub Vec<Aura>);
+
+/// The combined buffs from all auras reaching an entity.
+/// Entities outside all auras don't have it.
+#[derive(Component, Clone, Debug, Default, PartialEq, Reflect)]
+pub struct AuraBuffs {
+    /// Added attack speed as a fraction of the attack speed.
+    pub attack_speed: f32,
+    pub armor: i32,
+    /// Health restored every second.
+    pub health_regen: i32,
+}
+
+impl AuraBuffs {
+    pub fn attack_speed_multiplier(&self) -> f32 {
+        1. + self.attack_speed
+    }
+}
+
+/// Combines the values of auras of one type, following their stacking rules.
+/// Additive auras are summed up, and the strongest of the other auras is added to the sum.
+#[derive(Default)]
+struct StackedValue<T> {
+    additive: T,
+    strongest: Option<T>,
+}
+
+impl<T: Copy + PartialOrd + std::ops::Add<Output = T>> StackedValue<T> {
+    fn add(&mut self, value: T, stacking: AuraStacking) {
+        match stacking {
+            AuraStacking::Additive => self.additive = self.additive + value,
+            AuraStacking::Strongest => match self.strongest {
+                Some(strongest) if strongest >= value => {}
+                _ => self.strongest = Some(value),
+            },
+        }
+    }
+
+    fn total(&self) -> T {
+        match self.strongest {
+            Some(strongest) => self.additive + strongest,
+            None => self.additive,
+        }
+    }
+}
+
+#[derive(Default)]
+struct StackedBuffs {
+    attack_speed: StackedValue<f32>,
+    armor: StackedValue<i32>,
+    health_regen: StackedValue<i32>,
+}
+
+impl StackedBuffs {
+    fn add(&mut self, aura: &Aura) {
+        match aura.effect {
+            AuraEffect::AttackSpeed(value) => self.attack_speed.add(value, aura.stacking),
+            AuraEffect::Armor(value) => self.armor.add(value, aura.stacking),
+            AuraEffect::HealthRegen(value) => self.health_regen.add(value, aura.stacking),
+        }
+    }
+
+    fn total(&self) -> AuraBuffs {
+        AuraBuffs {
+            attack_speed: self.attack_speed.total(),
+            armor: self.armor.total(),
+            health_regen: self.health_regen.total(),
+        }
+    }
+}
+
+// --- Systems ---
+
+fn update_aura_buffs(
+    mut commands: Commands,
+    team_entity_tree: Res<TeamEntityTree>,
+    aura_query: Query<(&Transform, &Team, &Auras)>,
+    team_query: Query<&Team>,
+    buffed_query: Query<(Entity, &AuraBuffs)>,
+) {
+    let mut stacked_buffs: HashMap<Entity, StackedBuffs> = HashMap::new();
+
+    for (transform, team, auras) in aura_query.iter() {
+        for aura in auras.0.iter() {
+            for (_, opt_entity) in
+                team_entity_tree.within_distance(transform.translation.truncate(), aura.radius)
+            {
+                let Some(entity) = opt_entity else {
+                    continue;
+                };
+                if team_query
+                    .get(entity)
+                    .is_ok_and(|other_team| other_team == team)
+                {
+                    stacked_buffs.entry(entity).or_default().add(aura);
+                }
+            }
+        }
+    }
+
+    // Remove the buffs from entities that have left all auras.
+    for (entity, _) in buffed_query.iter() {
+        if !stacked_buffs.contains_key(&entity) {
+            commands.entity(entity).remove::<AuraBuffs>();
+        }
+    }
+
+    for (entity, stacked) in stacked_buffs {
+        let aura_buffs = stacked.total();
+        let unchanged = buffed_query
+            .get(entity)
+            .is_ok_and(|(_, current_buffs)| *current_buffs == aura_buffs);
+        if !unchanged {
+            if let Some(mut entity_commands) = commands.get_entity(entity) {
+                entity_commands.insert(aura_buffs);
+            }
+        }
+    }
+}
+
+fn regenerate_health(
+    query: Query<(Entity, &AuraBuffs, &Health)>,
+    mut ev_heal: EventWriter<HealEvent>,
+) {
+    for (entity, aura_buffs, health) in query.iter() {
+        if aura_buffs.health_regen > 0 && health.health < health.max_health {
+            ev_heal.send(HealEvent {
+                source: None,
+                target: entity,
+                amount: aura_buffs.health_regen,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_auras_add_nothing() {
+        let stacked_value: StackedValue<i32> = StackedValue::default();
+        assert_eq!(stacked_value.total(), 0);
+    }
+
+    #[test]
+    fn additive_auras_are_summed_up() {
+        let mut stacked_value = StackedValue::default();
+        stacked_value.add(2, AuraStacking::Additive);
+        stacked_value.add(3, AuraStacking::Additive);
+        assert_eq!(stacked_value.total(), 5);
+    }
+
+    #[test]
+    fn only_the_strongest_aura_counts() {
+        let mut stacked_value = StackedValue::default();
+        stacked_value.add(2, AuraStacking::Strongest);
+        stacked_value.add(4, AuraStacking::Strongest);
+        stacked_value.add(3, AuraStacking::Strongest);
+        assert_eq!(stacked_value.total(), 4);
+    }
+
+    #[test]
+    fn strongest_aura_adds_to_additive_auras() {
+        let mut stacked_value = StackedValue::default();
+        stacked_value.add(0.5, AuraStacking::Strongest);
+        stacked_value.add(0.25, AuraStacking::Additive);
+        stacked_value.add(0.25, AuraStacking::Strongest);
+        stacked_value.add(0.25, AuraStacking::Additive);
+        assert_eq!(stacked_value.total(), 1.);
+    }
+}
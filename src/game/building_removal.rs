@@ -0,0 +1,257 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::game::building_upgrades::UpgradingBuilding;
+use crate::game::buildings::{Building, Castle};
+use crate::game::construction::{cancel_value, UnderConstruction};
+use crate::game::economy::Treasuries;
+use crate::game::health::Health;
+use crate::game::research::ResearchQueue;
+use crate::game::selection::SelectedBuilding;
+use crate::game::spawning::BlueprintId;
+use crate::game::teams::Team;
+use crate::load_game::load_factions::BuildingBlueprint;
+use crate::resources::TeamSettings;
+
+/*
+Lets teams remove their own buildings, to make room for others.
+Selling refunds part of the cost, while demolishing refunds nothing.
+Selling a building under construction cancels the construction instead, which refunds its own share of the cost.
+Castles can't be removed. Research and upgrades that haven't finished are refunded in full.
+The player removes the selected building from its info panel, or with Delete to sell and Shift+Delete to demolish.
+*/
+
+// --- Plugin ---
+
+pub struct BuildingRemovalPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for BuildingRemovalPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RemoveBuildingEvent>().add_systems(
+            Update,
+            (remove_selected_building_on_key, remove_buildings)
+                .chain()
+                .run_if(in_state(self.state.clone())),
+        );
+    }
+}
+
+// --- Enums ---
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Removal {
+    /// Refunds part of the cost, less the more the building is damaged.
+    /// Cancels the construction of buildings under construction.
+    Sell,
+    /// Refunds nothing.
+    Demolish,
+}
+
+// --- Events ---
+
+#[derive(Event)]
+pub struct RemoveBuildingEvent {
+    pub building: Entity,
+    pub removal: Removal,
+}
+
+// --- Systems ---
+
+fn remove_selected_building_on_key(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    selected_building: Res<SelectedBuilding>,
+    mut ev_remove_building: EventWriter<RemoveBuildingEvent>,
+) {
+    let Some(building) = selected_building.0 else {
+        return;
+    };
+    if !keyboard_input.just_pressed(KeyCode::Delete) {
+        return;
+    }
+    let removal = if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        Removal::Demolish
+    } else {
+        Removal::Sell
+    };
+    ev_remove_building.send(RemoveBuildingEvent { building, removal });
+}
+
+#[allow(clippy::type_complexity)]
+fn remove_buildings(
+    mut commands: Commands,
+    mut ev_remove_building: EventReader<RemoveBuildingEvent>,
+    query: Query<
+        (
+            &Team,
+            &BlueprintId,
+            Option<&Health>,
+            Option<&ResearchQueue>,
+            Option<&UpgradingBuilding>,
+            Has<UnderConstruction>,
+        ),
+        (With<Building>, Without<Castle>),
+    >,
+    mut treasuries: ResMut<Treasuries>,
+    team_settings: Res<TeamSettings>,
+) {
+    // Each building can only be removed once, even if it was asked for more than once.
+    let mut removed: HashSet<Entity> = HashSet::new();
+
+    for ev in ev_remove_building.read() {
+        let Ok((
+            team,
+            blueprint_id,
+            opt_health,
+            opt_research_queue,
+            opt_upgrading_building,
+            is_under_construction,
+        )) = query.get(ev.building)
+        else {
+            continue;
+        };
+        // The dead are despawned by the health systems, and pay out bounties instead.
+        if opt_health.is_some_and(|health| health.health <= 0) || !removed.insert(ev.building) {
+            continue;
+        }
+
+        if ev.removal == Removal::Sell {
+            let refund = team_settings
+                .faction(*team)
+                .and_then(|faction| faction.buildings.get(&blueprint_id.0))
+                .map_or(0, |building_blueprint| {
+                    sell_value(building_blueprint, opt_health, is_under_construction)
+                });
+            treasuries.deposit(*team, refund);
+            if is_under_construction {
+                info!(
+                    "Team {} cancelled the construction of {:?} for {} gold.",
+                    team, blueprint_id.0, refund
+                );
+            } else {
+                info!(
+                    "Team {} sold {:?} for {} gold.",
+                    team, blueprint_id.0, refund
+                );
+            }
+        } else {
+            info!("Team {} demolished {:?}.", team, blueprint_id.0);
+        }
+
+        // The gold paid for research and upgrades that haven't finished isn't lost with the building.
+        let unspent = opt_research_queue.map_or(0, ResearchQueue::queued_cost)
+            + opt_upgrading_building.map_or(0, |upgrading_building| upgrading_building.cost);
+        if unspent > 0 {
+            treasuries.deposit(*team, unspent);
+            info!(
+                "Team {} was refunded {} gold for unfinished research and upgrades.",
+                team, unspent
+            );
+        }
+
+        commands.entity(ev.building).despawn_recursive();
+    }
+}
+
+// --- Helper functions ---
+
+/// The gold refunded for selling a building. Scaled by the share of health the building has left,
+/// unless the building is still under construction.
+pub fn sell_value(
+    building_blueprint: &BuildingBlueprint,
+    opt_health: Option<&Health>,
+    is_under_construction: bool,
+) -> u32 {
+    if is_under_construction {
+        return cancel_value(building_blueprint);
+    }
+    let health_share = opt_health.map_or(1., |health| {
+        (health.health as f32 / health.max_health.max(1) as f32).clamp(0., 1.)
+    });
+    (building_blueprint.cost as f32 * building_blueprint.sell_refund * health_share).floor() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn building_blueprint(cost: u32) -> BuildingBlueprint {
+        BuildingBlueprint {
+            id: "barracks".to_string(),
+            name: "Barracks".to_string(),
+            sprite: Handle::default(),
+            icon: Handle::default(),
+            cost,
+            bounty: 0,
+            tier: 1,
+            requires: vec![],
+            buildable: true,
+            sell_refund: 0.5,
+            cancel_refund: 0.75,
+            build_time: 10.,
+            upgrades_to: vec![],
+            components: vec![],
+        }
+    }
+
+    #[test]
+    fn full_health_refunds_the_sell_refund() {
+        let health = Health::new(200, 200);
+        assert_eq!(
+            sell_value(&building_blueprint(100), Some(&health), false),
+            50
+        );
+    }
+
+    #[test]
+    fn damaged_buildings_refund_less() {
+        let health = Health::new(100, 200);
+        assert_eq!(
+            sell_value(&building_blueprint(100), Some(&health), false),
+            25
+        );
+        let health = Health::new(1, 200);
+        assert_eq!(
+            sell_value(&building_blueprint(100), Some(&health), false),
+            0
+        );
+    }
+
+    #[test]
+    fn buildings_without_health_refund_the_sell_refund() {
+        assert_eq!(sell_value(&building_blueprint(100), None, false), 50);
+    }
+
+    #[test]
+    fn health_share_is_clamped() {
+        let health = Health {
+            max_health: 200,
+            health: 300,
+        };
+        assert_eq!(
+            sell_value(&building_blueprint(100), Some(&health), false),
+            50
+        );
+        let health = Health::new(-50, 200);
+        assert_eq!(
+            sell_value(&building_blueprint(100), Some(&health), false),
+            0
+        );
+        let health = Health::new(0, 0);
+        assert_eq!(
+            sell_value(&building_blueprint(100), Some(&health), false),
+            0
+        );
+    }
+
+    #[test]
+    fn buildings_under_construction_refund_the_cancel_refund() {
+        // Health doesn't matter, as the building's health is still ramping up.
+        let health = Health::new(20, 200);
+        assert_eq!(
+            sell_value(&building_blueprint(100), Some(&health), true),
+            75
+        );
+    }
+}
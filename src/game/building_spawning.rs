@@ -1,13 +1,21 @@
+use std::fmt;
+use std::fmt::Formatter;
+
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 use bevy_rapier2d::pipeline::CollisionEvent;
 
-use crate::game::buildings::{spawn_building, spawn_ghost_building, Building, BuildingGhost};
-use crate::game::grid_traits::SnapToGrid;
+use crate::game::buildings::{
+    count_owned_buildings, spawn_building, spawn_ghost_building, Building, BuildingGhost,
+};
+use crate::game::construction::UnderConstruction;
+use crate::game::economy::Treasuries;
+use crate::game::grid_traits::{SnapToGrid, GRID_SIZE};
 use crate::game::resources::MousePosition;
+use crate::game::spawning::BlueprintId;
 use crate::game::teams::Team;
 use crate::load_game::load_factions::BuildingBlueprint;
-use crate::resources::PlayerSettings;
+use crate::resources::TeamSettings;
 
 // --- Plugin ---
 
@@ -17,17 +25,21 @@ pub struct BuildingSpawningPlugin<S: States> {
 
 impl<S: States> Plugin for BuildingSpawningPlugin<S> {
     fn build(&self, app: &mut App) {
-        app.add_event::<InitPlaceBuildingEvent>().add_systems(
-            Update,
-            (
-                on_init_place_building,
-                update_ghost_building_position.after(on_init_place_building),
-                cancel_building,
-                ghost_building_collision_system,
-                building_placement,
-            )
-                .run_if(in_state(self.state.clone())),
-        );
+        app.add_event::<InitPlaceBuildingEvent>()
+            .add_event::<PlaceBuildingEvent>()
+            .add_event::<BuildingPlacementRefusedEvent>()
+            .add_systems(
+                Update,
+                (
+                    on_init_place_building,
+                    update_ghost_building_position.after(on_init_place_building),
+                    cancel_building,
+                    ghost_building_collision_system,
+                    building_placement,
+                    place_building.after(building_placement),
+                )
+                    .run_if(in_state(self.state.clone())),
+            );
     }
 }
 
@@ -36,6 +48,50 @@ impl<S: States> Plugin for BuildingSpawningPlugin<S> {
 #[derive(Event)]
 pub struct InitPlaceBuildingEvent(pub BuildingBlueprint, pub Team);
 
+/// Places a building for a team, if the team can afford it.
+/// Both the player and the computer place their buildings through this event.
+#[derive(Event)]
+pub struct PlaceBuildingEvent {
+    pub building_blueprint: BuildingBlueprint,
+    pub team: Team,
+    pub position: Vec2,
+    /// The ghost used to position the building. It is removed once the building has been placed.
+    pub ghost: Option<Entity>,
+}
+
+/// Sent when a team tried to place a building, but wasn't allowed to.
+#[derive(Event)]
+pub struct BuildingPlacementRefusedEvent {
+    pub team: Team,
+    pub reason: PlacementRefusal,
+}
+
+// --- Enums ---
+
+pub enum PlacementRefusal {
+    NotEnoughGold {
+        cost: u32,
+        gold: u32,
+    },
+    /// The team doesn't own the named building, which the building requires.
+    MissingRequirement {
+        building: String,
+    },
+}
+
+impl fmt::Display for PlacementRefusal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PlacementRefusal::NotEnoughGold { cost, gold } => {
+                write!(f, "Not enough gold! Costs {} but you have {}.", cost, gold)
+            }
+            PlacementRefusal::MissingRequirement { building } => {
+                write!(f, "Requires a {}.", building)
+            }
+        }
+    }
+}
+
 // --- Systems ---
 
 fn on_init_place_building(
@@ -71,7 +127,7 @@ fn update_ghost_building_position(
     if let Ok(mut ghost_transform) = query.get_single_mut() {
         let z = ghost_transform.translation.z;
         ghost_transform.translation =
-            Vec3::new(mouse_position.x, mouse_position.y, z).snap_to_grid(32.);
+            Vec3::new(mouse_position.x, mouse_position.y, z).snap_to_grid(GRID_SIZE);
     }
 }
 
@@ -146,22 +202,77 @@ fn ghost_building_collision_system(
 }
 
 fn building_placement(
-    mut commands: Commands,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     mut ghost_query: Query<(Entity, &BuildingGhost, &Transform), With<BuildingGhost>>,
-    player_settings: Res<PlayerSettings>,
+    mut ev_place_building: EventWriter<PlaceBuildingEvent>,
 ) {
     if let Ok((ghost_entity, ghost_building, ghost_transform)) = ghost_query.get_single_mut() {
         if ghost_building.placement_valid && mouse_button_input.just_pressed(MouseButton::Left) {
+            ev_place_building.send(PlaceBuildingEvent {
+                building_blueprint: ghost_building.building_blueprint.clone(),
+                team: ghost_building.team,
+                position: ghost_transform.translation.truncate(),
+                ghost: Some(ghost_entity),
+            });
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn place_building(
+    mut commands: Commands,
+    mut ev_place_building: EventReader<PlaceBuildingEvent>,
+    team_settings: Res<TeamSettings>,
+    mut treasuries: ResMut<Treasuries>,
+    mut ev_placement_refused: EventWriter<BuildingPlacementRefusedEvent>,
+    // Buildings under construction don't count towards requirements.
+    building_query: Query<(&Team, &BlueprintId), (With<Building>, Without<UnderConstruction>)>,
+) {
+    for ev in ev_place_building.read() {
+        let owned_buildings = count_owned_buildings(ev.team, building_query.iter());
+        if let Some(required) = ev.building_blueprint.missing_requirement(&owned_buildings) {
+            ev_placement_refused.send(BuildingPlacementRefusedEvent {
+                team: ev.team,
+                reason: PlacementRefusal::MissingRequirement {
+                    building: building_name(&team_settings, ev.team, required),
+                },
+            });
+            continue;
+        }
+
+        let cost = ev.building_blueprint.cost;
+        // Keep the ghost around, so the player can place it once they can afford it.
+        if !treasuries.try_spend(ev.team, cost) {
+            ev_placement_refused.send(BuildingPlacementRefusedEvent {
+                team: ev.team,
+                reason: PlacementRefusal::NotEnoughGold {
+                    cost,
+                    gold: treasuries.gold(ev.team),
+                },
+            });
+            continue;
+        }
+
+        if let Some(ghost_entity) = ev.ghost {
             commands.entity(ghost_entity).despawn_recursive();
-            spawn_building(
-                &mut commands,
-                ghost_building.team,
-                ghost_transform.translation.x,
-                ghost_transform.translation.y,
-                ghost_building.building_blueprint.clone(),
-                &player_settings,
-            )
         }
+        spawn_building(
+            &mut commands,
+            ev.team,
+            ev.position.x,
+            ev.position.y,
+            ev.building_blueprint.clone(),
+            &team_settings,
+        )
     }
 }
+
+// --- Helper functions ---
+
+/// The name of one of the team's buildings, falling back to the ID.
+pub fn building_name(team_settings: &TeamSettings, team: Team, building_id: &str) -> String {
+    team_settings
+        .faction(team)
+        .and_then(|faction| faction.buildings.get(building_id))
+        .map_or(building_id.to_string(), |building| building.name.clone())
+}
@@ -0,0 +1,316 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::building_spawning::building_name;
+use crate::game::buildings::{count_owned_buildings, Building};
+use crate::game::construction::UnderConstruction;
+use crate::game::damage::{apply_damage, DeathEvent};
+use crate::game::economy::{Bounty, Treasuries};
+use crate::game::health::{check_death, Health};
+use crate::game::research::ResearchQueue;
+use crate::game::spawning::{add_blueprint_components, remove_blueprint_components, BlueprintId};
+use crate::game::teams::Team;
+use crate::load_game::load_factions::{BuildingBlueprint, ComponentBlueprint};
+use crate::resources::TeamSettings;
+
+/*
+Upgrades that turn a building into another building in place, for instance Barracks into Fortified Barracks.
+The building keeps its position, team and the same share of its health,
+but gets the sprite and components of the building it is upgraded into.
+Upgrades are paid when they start, and refunded if the building is lost before they finish.
+*/
+
+// --- Plugin ---
+
+pub struct BuildingUpgradePlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for BuildingUpgradePlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<UpgradeBuildingEvent>()
+            .add_event::<BuildingUpgradeRefusedEvent>()
+            .add_systems(
+                Update,
+                (start_building_upgrades, progress_building_upgrades)
+                    .chain()
+                    .run_if(in_state(self.state.clone())),
+            )
+            .add_systems(
+                PostUpdate,
+                refund_upgrades_of_destroyed_buildings
+                    .after(apply_damage)
+                    .before(check_death)
+                    .run_if(in_state(self.state.clone())),
+            );
+    }
+}
+
+// --- Enums ---
+
+pub enum BuildingUpgradeRefusal {
+    NotEnoughGold {
+        cost: u32,
+        gold: u32,
+    },
+    /// The team doesn't own the named building, which the upgraded building requires.
+    MissingRequirement {
+        building: String,
+    },
+    AlreadyUpgrading,
+    UnderConstruction,
+}
+
+impl fmt::Display for BuildingUpgradeRefusal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildingUpgradeRefusal::NotEnoughGold { cost, gold } => {
+                write!(f, "Not enough gold! Costs {} but you have {}.", cost, gold)
+            }
+            BuildingUpgradeRefusal::MissingRequirement { building } => {
+                write!(f, "Requires a {}.", building)
+            }
+            BuildingUpgradeRefusal::AlreadyUpgrading => write!(f, "Already being upgraded."),
+            BuildingUpgradeRefusal::UnderConstruction => write!(f, "Still under construction."),
+        }
+    }
+}
+
+// --- Types ---
+
+/// A building another building can be upgraded into. Defined in the faction files.
+#[derive(Deserialize, Clone, PartialEq, Debug, Reflect)]
+pub struct BuildingUpgradeBlueprint {
+    /// The ID of the building to upgrade into.
+    pub building_id: String,
+    /// Gold it costs to upgrade. Paid when the upgrade starts.
+    pub cost: u32,
+    /// Seconds the upgrade takes.
+    pub upgrade_time: f32,
+}
+
+// --- Components ---
+
+/// A building being upgraded into another building.
+#[derive(Component)]
+pub struct UpgradingBuilding {
+    pub building_blueprint: BuildingBlueprint,
+    /// The gold paid for the upgrade.
+    pub cost: u32,
+    pub progress: Timer,
+}
+
+// --- Events ---
+
+/// Asks a building to start upgrading into another building, if its team can afford it.
+#[derive(Event)]
+pub struct UpgradeBuildingEvent {
+    pub building: Entity,
+    pub building_id: String,
+}
+
+/// Sent when a team tried to upgrade a building, but wasn't allowed to.
+#[derive(Event)]
+pub struct BuildingUpgradeRefusedEvent {
+    pub team: Team,
+    pub reason: BuildingUpgradeRefusal,
+}
+
+// --- Systems ---
+
+#[allow(clippy::type_complexity)]
+fn start_building_upgrades(
+    mut commands: Commands,
+    mut ev_upgrade_building: EventReader<UpgradeBuildingEvent>,
+    mut ev_upgrade_refused: EventWriter<BuildingUpgradeRefusedEvent>,
+    query: Query<
+        (
+            &Team,
+            &BlueprintId,
+            Has<UpgradingBuilding>,
+            Has<UnderConstruction>,
+        ),
+        With<Building>,
+    >,
+    mut treasuries: ResMut<Treasuries>,
+    team_settings: Res<TeamSettings>,
+) {
+    for ev in ev_upgrade_building.read() {
+        let Ok((team, blueprint_id, is_upgrading, is_under_construction)) = query.get(ev.building)
+        else {
+            continue;
+        };
+        let team = *team;
+        let Some(faction) = team_settings.faction(team) else {
+            continue;
+        };
+        let Some(upgrade) = faction.buildings.get(&blueprint_id.0).and_then(|building| {
+            building
+                .upgrades_to
+                .iter()
+                .find(|upgrade| upgrade.building_id == ev.building_id)
+        }) else {
+            error!(
+                "{:?} can't be upgraded into {:?}.",
+                blueprint_id.0, ev.building_id
+            );
+            continue;
+        };
+        let Some(building_blueprint) = faction.buildings.get(&upgrade.building_id) else {
+            error!(
+                "Building {:?} doesn't exist in faction {:?}.",
+                upgrade.building_id, faction.id
+            );
+            continue;
+        };
+
+        let mut refuse = |reason: BuildingUpgradeRefusal| {
+            ev_upgrade_refused.send(BuildingUpgradeRefusedEvent { team, reason });
+        };
+
+        if is_under_construction {
+            refuse(BuildingUpgradeRefusal::UnderConstruction);
+            continue;
+        }
+        if is_upgrading {
+            refuse(BuildingUpgradeRefusal::AlreadyUpgrading);
+            continue;
+        }
+        let owned_buildings = count_owned_buildings(
+            team,
+            // Buildings under construction don't count towards requirements.
+            query
+                .iter()
+                .filter(|(_, _, _, is_under_construction)| !is_under_construction)
+                .map(|(building_team, blueprint_id, _, _)| (building_team, blueprint_id)),
+        );
+        if let Some(required) = building_blueprint.missing_requirement(&owned_buildings) {
+            refuse(BuildingUpgradeRefusal::MissingRequirement {
+                building: building_name(&team_settings, team, required),
+            });
+            continue;
+        }
+        if !treasuries.try_spend(team, upgrade.cost) {
+            refuse(BuildingUpgradeRefusal::NotEnoughGold {
+                cost: upgrade.cost,
+                gold: treasuries.gold(team),
+            });
+            continue;
+        }
+
+        commands.entity(ev.building).insert(UpgradingBuilding {
+            building_blueprint: building_blueprint.clone(),
+            cost: upgrade.cost,
+            progress: Timer::from_seconds(upgrade.upgrade_time, TimerMode::Once),
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn progress_building_upgrades(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &Team,
+        &mut UpgradingBuilding,
+        Option<&Health>,
+        Option<&ResearchQueue>,
+    )>,
+    mut treasuries: ResMut<Treasuries>,
+    team_settings: Res<TeamSettings>,
+    time: Res<Time>,
+) {
+    for (entity, team, mut upgrading_building, opt_health, opt_research_queue) in query.iter_mut() {
+        // Ticking only changes the progress, so it shouldn't count as a change to the upgrade.
+        if !upgrading_building
+            .bypass_change_detection()
+            .progress
+            .tick(time.delta())
+            .finished()
+        {
+            continue;
+        }
+        let Some(faction) = team_settings.faction(*team) else {
+            continue;
+        };
+        let building_blueprint = &upgrading_building.building_blueprint;
+
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.remove::<UpgradingBuilding>();
+
+        // Replace everything that came from the old blueprint.
+        remove_blueprint_components(&mut entity_commands);
+        // Removed first, so the new ID counts as added and the team's research is applied to it.
+        entity_commands.remove::<BlueprintId>();
+        entity_commands.insert((
+            BlueprintId(building_blueprint.id.clone()),
+            building_blueprint.sprite.clone(),
+            Bounty(building_blueprint.bounty),
+            Name::new(format!(
+                "Building: {} - Team: {}",
+                building_blueprint.name, team
+            )),
+        ));
+        add_blueprint_components(
+            &mut entity_commands,
+            &building_blueprint.components,
+            faction,
+        );
+
+        // Keep the same share of health.
+        let new_max_health = building_blueprint
+            .components
+            .iter()
+            .find_map(|component_blueprint| match component_blueprint {
+                ComponentBlueprint::Health { max_health, .. } => Some(*max_health),
+                _ => None,
+            });
+        if let (Some(health), Some(new_max_health)) = (opt_health, new_max_health) {
+            let share = health.health as f32 / health.max_health.max(1) as f32;
+            let new_health = ((new_max_health as f32 * share).round() as i32).max(1);
+            entity_commands.insert(Health::new(new_health, new_max_health));
+        }
+
+        // Keep the research queue, if the new building researches too. Otherwise the queued research is refunded.
+        let researches = faction
+            .upgrades
+            .values()
+            .any(|upgrade| upgrade.researched_at == building_blueprint.id);
+        match (opt_research_queue, researches) {
+            (None, true) => {
+                entity_commands.insert(ResearchQueue::default());
+            }
+            (Some(research_queue), false) => {
+                treasuries.deposit(*team, research_queue.queued_cost());
+                entity_commands.remove::<ResearchQueue>();
+            }
+            _ => {}
+        }
+
+        info!(
+            "{:?} of team {} was upgraded into {}!",
+            entity, team, building_blueprint.name
+        );
+    }
+}
+
+/// Refunds the upgrades of buildings that died while upgrading. Should run before the dead are despawned.
+fn refund_upgrades_of_destroyed_buildings(
+    mut ev_death: EventReader<DeathEvent>,
+    query: Query<&UpgradingBuilding>,
+    mut treasuries: ResMut<Treasuries>,
+) {
+    for event in ev_death.read() {
+        let Ok(upgrading_building) = query.get(event.entity) else {
+            continue;
+        };
+        treasuries.deposit(event.team, upgrading_building.cost);
+        info!(
+            "Team {} was refunded {} gold for the upgrade lost with {:?}.",
+            event.team, upgrading_building.cost, event.entity
+        );
+    }
+}
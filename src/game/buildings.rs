@@ -1,11 +1,26 @@
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 use bevy_rapier2d::prelude::*;
 
-use crate::game::spawning::add_blueprint_components;
+use crate::game::attack::BodyRadius;
+use crate::game::construction::{
+    spawn_construction_overlay, UnderConstruction, CONSTRUCTION_START_HEALTH,
+};
+use crate::game::economy::Bounty;
+use crate::game::health::Health;
+use crate::game::movement::Stationary;
+use crate::game::research::ResearchQueue;
+use crate::game::spawning::{add_blueprint_components, BlueprintId};
 use crate::game::teams::Team;
+use crate::game::unit_spawning::RallyPoint;
 use crate::game::InGameTag;
-use crate::load_game::load_factions::BuildingBlueprint;
-use crate::resources::PlayerSettings;
+use crate::load_game::load_factions::{BuildingBlueprint, ComponentBlueprint};
+use crate::resources::TeamSettings;
+
+// --- Constants ---
+
+/// Half the width of a building's footprint, matching the size of its sprite.
+pub const BUILDING_HALF_SIZE: f32 = 32.;
 
 // --- Components ---
 
@@ -24,6 +39,20 @@ pub struct BuildingGhost {
 
 // --- Helper functions ---
 
+/// Counts the buildings a team owns of each kind.
+pub fn count_owned_buildings<'a>(
+    team: Team,
+    buildings: impl Iterator<Item = (&'a Team, &'a BlueprintId)>,
+) -> HashMap<&'a str, usize> {
+    let mut owned_buildings: HashMap<&str, usize> = HashMap::new();
+    for (building_team, blueprint_id) in buildings {
+        if *building_team == team {
+            *owned_buildings.entry(blueprint_id.0.as_str()).or_insert(0) += 1;
+        }
+    }
+    owned_buildings
+}
+
 /// Helper function to spawn a building. This is not a system.
 pub fn spawn_building(
     commands: &mut Commands,
@@ -31,19 +60,29 @@ pub fn spawn_building(
     x: f32,
     y: f32,
     building_blueprint: BuildingBlueprint,
-    player_settings: &Res<PlayerSettings>,
+    team_settings: &TeamSettings,
 ) {
+    let Some(faction) = team_settings.faction(team) else {
+        error!("Team {} has no faction to spawn buildings from.", team);
+        return;
+    };
+
     let mut building_entity = commands.spawn((
         InGameTag,
         team,
         Building,
+        BlueprintId(building_blueprint.id.clone()),
         SpriteBundle {
             texture: building_blueprint.sprite.clone(),
             transform: Transform::from_xyz(x, y, 10.),
             ..Default::default()
         },
         RigidBody::KinematicPositionBased,
-        Collider::cuboid(32.0, 32.0), // Actual collider matching sprite size.
+        Collider::cuboid(BUILDING_HALF_SIZE, BUILDING_HALF_SIZE), // Actual collider matching sprite size.
+        BodyRadius(BUILDING_HALF_SIZE),
+        Bounty(building_blueprint.bounty),
+        Stationary,
+        RallyPoint::default(),
     ));
     building_entity.insert(Name::new(format!(
         "Building: {} - Team: {}",
@@ -54,10 +93,37 @@ pub fn spawn_building(
     add_blueprint_components(
         &mut building_entity,
         &building_blueprint.components,
-        player_settings,
+        faction,
     );
 
-    let text_color = team.get_color();
+    // Only buildings that research upgrades get a research queue.
+    if faction
+        .upgrades
+        .values()
+        .any(|upgrade| upgrade.researched_at == building_blueprint.id)
+    {
+        building_entity.insert(ResearchQueue::default());
+    }
+
+    // Buildings with a build time start out under construction, with a fraction of their health.
+    let under_construction = building_blueprint.build_time > 0.;
+    if under_construction {
+        building_entity.insert(UnderConstruction::new(building_blueprint.build_time));
+        if let Some(max_health) =
+            building_blueprint
+                .components
+                .iter()
+                .find_map(|component_blueprint| match component_blueprint {
+                    ComponentBlueprint::Health { max_health, .. } => Some(*max_health),
+                    _ => None,
+                })
+        {
+            let health = ((max_health as f32 * CONSTRUCTION_START_HEALTH).round() as i32).max(1);
+            building_entity.insert(Health::new(health, max_health));
+        }
+    }
+
+    let text_color = team_settings.color(team);
 
     building_entity.with_children(|builder| {
         builder.spawn(Text2dBundle {
@@ -74,6 +140,9 @@ pub fn spawn_building(
             transform: Transform::from_translation(Vec3::new(0.0, -40.0, 1.0)),
             ..Default::default()
         });
+        if under_construction {
+            spawn_construction_overlay(builder);
+        }
     });
 }
 
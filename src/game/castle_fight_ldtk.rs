@@ -2,11 +2,18 @@ use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+use crate::game::attack::{AttackStats, BodyRadius};
 use crate::game::buildings::{Building, Castle};
+use crate::game::damage::DamageType;
 use crate::game::health::Health;
+use crate::game::movement::Stationary;
+use crate::game::projectiles::ProjectileLauncher;
+use crate::game::targeting::{AttackCandidates, TargetRule, TargetingRules};
 use crate::game::teams::{Team, TeamAssociation};
+use crate::game::vision::{InVision, VisionRange};
 use crate::game::waypoints::{IsStartPoint, Waypoint};
 use crate::game::InGameTag;
+use crate::resources::TeamSettings;
 
 /*
 Handles all LDtk bundles and processing and resolving of these components.
@@ -20,16 +27,37 @@ pub struct CastleFightLdtkPlugin<S: States> {
 
 impl<S: States> Plugin for CastleFightLdtkPlugin<S> {
     fn build(&self, app: &mut App) {
-        app.register_ldtk_entity::<CastleBundle>("Castle")
+        app.init_resource::<LevelBounds>()
+            .register_ldtk_entity::<CastleBundle>("Castle")
             .register_ldtk_entity::<WaypointBundle>("Waypoint")
             .add_systems(
                 Update,
-                (process_castle, resolve_next_waypoint_references)
+                (
+                    process_castle,
+                    resolve_next_waypoint_references,
+                    update_level_bounds,
+                )
                     .run_if(in_state(self.state.clone())),
             );
     }
 }
 
+// --- Constants ---
+
+const CASTLE_BODY_RADIUS: f32 = 96. / 2.;
+/// How much further castles see than they can attack, so targets are spotted before they are in range.
+const CASTLE_VISION_MARGIN: f32 = 32.;
+/// The arrows shot by castles. Drawn as a rectangle in the team color.
+const CASTLE_PROJECTILE_SPEED: f32 = 320.;
+const CASTLE_PROJECTILE_SIZE: Vec2 = Vec2::new(12., 2.);
+const CASTLE_PROJECTILE_ARC_HEIGHT: f32 = 16.;
+
+// --- Resources ---
+
+/// The area covered by the level in world space. `None` until the level has been spawned.
+#[derive(Resource, Default)]
+pub struct LevelBounds(pub Option<Rect>);
+
 // --- Components ---
 
 #[derive(Default, Bundle, LdtkEntity)]
@@ -37,6 +65,7 @@ pub struct CastleBundle {
     in_game_tag: InGameTag,
     castle: Castle,
     building: Building,
+    stationary: Stationary,
     #[sprite_bundle]
     sprite_bundle: SpriteBundle,
     #[with(Team::from_field)]
@@ -112,24 +141,47 @@ fn resolve_next_waypoint_references(
     }
 }
 
-fn process_castle(mut commands: Commands, new_castles: Query<(Entity, &Team), Added<Castle>>) {
-    for (entity, team) in new_castles.iter() {
+fn process_castle(
+    mut commands: Commands,
+    new_castles: Query<(Entity, &Team, &EntityInstance), Added<Castle>>,
+    team_settings: Res<TeamSettings>,
+) {
+    for (entity, team, entity_instance) in new_castles.iter() {
         let mut castle = commands.entity(entity);
         castle.insert((
             RigidBody::KinematicPositionBased,
-            // Add below back, if building has attack and a vision range.
-            // Collider::cuboid(32.0 * 2, 32.0 * 2),
-            // Sensor,
             CollisionGroups::new(Group::GROUP_2, Group::GROUP_1),
             ActiveCollisionTypes::all(), // TODO: Optimize later.
             ActiveEvents::COLLISION_EVENTS,
+            BodyRadius(CASTLE_BODY_RADIUS),
         ));
 
-        let text_color = team.get_color();
+        // Castles with attack damage defend themselves by shooting arrows.
+        if let Some(attack_stats) = castle_attack_stats(entity_instance) {
+            castle.insert((
+                VisionRange(CASTLE_BODY_RADIUS + attack_stats.attack_range + CASTLE_VISION_MARGIN),
+                InVision {
+                    friendlies: vec![],
+                    enemies: vec![],
+                },
+                AttackCandidates::default(),
+                TargetingRules(vec![TargetRule::PreferUnits, TargetRule::Closest]),
+                ProjectileLauncher {
+                    speed: CASTLE_PROJECTILE_SPEED,
+                    sprite: None,
+                    size: Some(CASTLE_PROJECTILE_SIZE),
+                    homing: true,
+                    arc_height: CASTLE_PROJECTILE_ARC_HEIGHT,
+                },
+                attack_stats,
+            ));
+        }
+
+        let text_color = team_settings.color(*team);
 
         castle.with_children(|builder| {
             builder.spawn((
-                Collider::cuboid(96. / 2., 96. / 2.), // Actual collider matching sprite size.
+                Collider::cuboid(CASTLE_BODY_RADIUS, CASTLE_BODY_RADIUS), // Actual collider matching sprite size.
                 CollisionGroups::new(Group::GROUP_1, Group::GROUP_2 | Group::GROUP_3),
             ));
         });
@@ -152,3 +204,55 @@ fn process_castle(mut commands: Commands, new_castles: Query<(Entity, &Team), Ad
         });
     }
 }
+
+/// Stores the area of the level, once it has been spawned.
+fn update_level_bounds(
+    level_query: Query<(&LevelIid, &Transform), Added<LevelIid>>,
+    ldtk_project_query: Query<&Handle<LdtkProject>>,
+    ldtk_projects: Res<Assets<LdtkProject>>,
+    mut level_bounds: ResMut<LevelBounds>,
+) {
+    for (level_iid, transform) in level_query.iter() {
+        let Some(level) = ldtk_project_query
+            .iter()
+            .find_map(|handle| ldtk_projects.get(handle))
+            .and_then(|ldtk_project| ldtk_project.get_raw_level_by_iid(&level_iid.to_string()))
+        else {
+            continue;
+        };
+        // Levels are spawned with their bottom left corner at their transform.
+        let corner = transform.translation.truncate();
+        level_bounds.0 = Some(Rect::from_corners(
+            corner,
+            corner + Vec2::new(level.px_wid as f32, level.px_hei as f32),
+        ));
+    }
+}
+
+// --- Helper functions ---
+
+/// Reads the attack of a castle from its LDtk fields.
+/// Castles without attack damage don't attack.
+fn castle_attack_stats(entity_instance: &EntityInstance) -> Option<AttackStats> {
+    let damage = entity_instance
+        .get_maybe_int_field("attackDamage")
+        .ok()
+        .copied()
+        .flatten()
+        .filter(|damage| *damage > 0)?;
+    let attack_speed = entity_instance
+        .get_float_field("attackSpeed")
+        .copied()
+        .unwrap_or(1.);
+    let attack_range = entity_instance
+        .get_int_field("attackRange")
+        .copied()
+        .unwrap_or(96);
+
+    Some(AttackStats::new(
+        damage,
+        DamageType::Pierce,
+        attack_speed,
+        attack_range as f32,
+    ))
+}
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::game::health::Health;
+use crate::load_game::load_factions::BuildingBlueprint;
+
+/*
+Buildings with a build time are constructed after they are placed.
+While under construction, the health of the building ramps up from a fraction of its maximum health,
+and the building doesn't spawn units, provide supply, research or attack.
+The player can cancel the construction for a refund of part of the cost.
+*/
+
+// --- Plugin ---
+
+pub struct ConstructionPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for ConstructionPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (progress_construction, update_construction_overlays)
+                .chain()
+                .run_if(in_state(self.state.clone())),
+        );
+    }
+}
+
+// --- Constants ---
+
+/// The share of the maximum health buildings start with when placed.
+pub const CONSTRUCTION_START_HEALTH: f32 = 0.1;
+const OVERLAY_SIZE: Vec2 = Vec2::new(64., 64.);
+
+// --- Components ---
+
+#[derive(Component)]
+pub struct UnderConstruction {
+    pub progress: Timer,
+    /// The health the construction has added so far, on top of the health the building started with.
+    health_added: i32,
+}
+
+impl UnderConstruction {
+    pub fn new(build_time: f32) -> UnderConstruction {
+        UnderConstruction {
+            progress: Timer::from_seconds(build_time, TimerMode::Once),
+            health_added: 0,
+        }
+    }
+}
+
+/// Covers the part of a building that hasn't been built yet. A child of the building.
+#[derive(Component)]
+pub struct ConstructionOverlay;
+
+// --- Systems ---
+
+fn progress_construction(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &mut UnderConstruction,
+        Option<&mut Health>,
+        Option<&Children>,
+    )>,
+    overlay_query: Query<(), With<ConstructionOverlay>>,
+    time: Res<Time>,
+) {
+    for (entity, mut under_construction, opt_health, opt_children) in query.iter_mut() {
+        // Ticking only changes the progress, so it shouldn't count as a change to the construction.
+        let under_construction = under_construction.bypass_change_detection();
+        under_construction.progress.tick(time.delta());
+
+        // Health the building took as damage stays lost, like in the rest of the game.
+        if let Some(mut health) = opt_health {
+            let total_added = (health.max_health as f32
+                * (1. - CONSTRUCTION_START_HEALTH)
+                * under_construction.progress.fraction())
+            .round() as i32;
+            health.heal(total_added - under_construction.health_added);
+            under_construction.health_added = total_added;
+        }
+
+        if !under_construction.progress.finished() {
+            continue;
+        }
+
+        commands.entity(entity).remove::<UnderConstruction>();
+        for child in opt_children
+            .into_iter()
+            .flat_map(|children| children.iter())
+        {
+            if overlay_query.contains(*child) {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+        info!("{:?} finished construction.", entity);
+    }
+}
+
+/// Shrinks the overlay from the bottom up, as the construction progresses.
+fn update_construction_overlays(
+    query: Query<(&UnderConstruction, &Children)>,
+    mut overlay_query: Query<&mut Sprite, With<ConstructionOverlay>>,
+) {
+    for (under_construction, children) in query.iter() {
+        for child in children.iter() {
+            if let Ok(mut sprite) = overlay_query.get_mut(*child) {
+                sprite.custom_size = Some(Vec2::new(
+                    OVERLAY_SIZE.x,
+                    OVERLAY_SIZE.y * (1. - under_construction.progress.fraction()),
+                ));
+            }
+        }
+    }
+}
+
+// --- Helper functions ---
+
+/// The gold refunded for cancelling the construction of a building.
+pub fn cancel_value(building_blueprint: &BuildingBlueprint) -> u32 {
+    (building_blueprint.cost as f32 * building_blueprint.cancel_refund).floor() as u32
+}
+
+/// Helper function to spawn the overlay of a building under construction. This is not a system.
+pub fn spawn_construction_overlay(builder: &mut ChildBuilder) {
+    builder.spawn((
+        ConstructionOverlay,
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.35, 0.25, 0.15, 0.75),
+                custom_size: Some(OVERLAY_SIZE),
+                anchor: Anchor::TopCenter,
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0., OVERLAY_SIZE.y / 2., 0.5),
+            ..Default::default()
+        },
+    ));
+}
@@ -0,0 +1,263 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::auras::AuraBuffs;
+use crate::game::health::{check_death, Health};
+use crate::game::teams::Team;
+use crate::load_game::load_damage_table::DamageTable;
+
+/*
+Damage types, armor types and the calculation of how much damage an attack deals to a defender.
+How effective each damage type is against each armor type is defined in the damage table asset.
+
+All damage is dealt by sending a `DamageEvent`, so armor is applied in one place
+and other systems can react to damage and deaths without touching the combat code.
+*/
+
+// --- Plugin ---
+
+pub struct DamagePlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for DamagePlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<DeathEvent>()
+            .add_systems(
+                PostUpdate,
+                apply_damage
+                    .before(check_death)
+                    .run_if(in_state(self.state.clone())),
+            );
+    }
+}
+
+// --- Constants ---
+
+/// How much each point of armor reduces damage. Works like in Warcraft III.
+const ARMOR_REDUCTION_PER_POINT: f32 = 0.06;
+
+// --- Enums ---
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Reflect)]
+pub enum DamageType {
+    #[default]
+    Normal,
+    Pierce,
+    Siege,
+    Magic,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Reflect)]
+pub enum ArmorType {
+    #[default]
+    Unarmored,
+    Light,
+    Medium,
+    Heavy,
+    Fortified,
+}
+
+/// What caused the damage. Decides how the damage is mitigated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DamageKind {
+    /// A direct hit from an attack or a projectile.
+    Attack(DamageType),
+    /// Area damage around the point of impact.
+    Splash(DamageType),
+    /// Damage over time from a burn status effect. Ignores armor.
+    Burn,
+}
+
+// --- Types ---
+
+/// Who dealt the damage. The team is kept, as the entity may have died since.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+pub struct DamageSource {
+    pub entity: Entity,
+    pub team: Team,
+}
+
+// --- Events ---
+
+/// Damages the target. The amount is before armor is applied.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DamageEvent {
+    pub source: Option<DamageSource>,
+    pub target: Entity,
+    pub amount: i32,
+    pub kind: DamageKind,
+}
+
+/// Sent when damage depletes the health of an entity. It is despawned afterwards.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DeathEvent {
+    pub entity: Entity,
+    /// The source of the final blow.
+    pub killer: Option<DamageSource>,
+    pub team: Team,
+}
+
+// --- Components ---
+
+/// Defensive stats. Entities without it are treated as unarmored with no armor.
+#[derive(Component, Debug, Default, Reflect)]
+pub struct Defense {
+    pub armor_type: ArmorType,
+    /// Each point of armor reduces the damage taken a bit.
+    pub armor: i32,
+}
+
+// --- Systems ---
+
+/// Applies the damage from all damage events. Should run after everything dealing damage,
+/// but before the dead are despawned.
+pub fn apply_damage(
+    mut ev_damage: EventReader<DamageEvent>,
+    mut ev_death: EventWriter<DeathEvent>,
+    mut query: Query<(&mut Health, Option<&Defense>, Option<&AuraBuffs>, &Team)>,
+    damage_table: Res<DamageTable>,
+) {
+    for event in ev_damage.read() {
+        let Ok((mut health, opt_defense, opt_aura_buffs, team)) = query.get_mut(event.target)
+        else {
+            continue;
+        };
+        // Already dead, but not yet despawned.
+        if health.health <= 0 {
+            continue;
+        }
+
+        let damage = match event.kind {
+            DamageKind::Attack(damage_type) | DamageKind::Splash(damage_type) => {
+                // Armor from auras adds to the armor of the defense.
+                let defense = Defense {
+                    armor_type: opt_defense
+                        .map_or(ArmorType::default(), |defense| defense.armor_type),
+                    armor: opt_defense.map_or(0, |defense| defense.armor)
+                        + opt_aura_buffs.map_or(0, |aura_buffs| aura_buffs.armor),
+                };
+                calculate_damage(event.amount, damage_type, Some(&defense), &damage_table)
+            }
+            DamageKind::Burn => event.amount.max(0),
+        };
+        health.health -= damage;
+        debug!("{:?} damage taken from {:?}!", damage, event.kind);
+
+        if health.health <= 0 {
+            ev_death.send(DeathEvent {
+                entity: event.target,
+                killer: event.source,
+                team: *team,
+            });
+        }
+    }
+}
+
+// --- Helper functions ---
+
+/// Calculates the damage dealt to a defender, taking damage effectiveness and armor into account.
+/// Attacks always deal at least 1 damage, unless the damage type has no effect on the armor type.
+pub fn calculate_damage(
+    damage: i32,
+    damage_type: DamageType,
+    opt_defense: Option<&Defense>,
+    damage_table: &DamageTable,
+) -> i32 {
+    let (armor_type, armor) = opt_defense.map_or((ArmorType::default(), 0), |defense| {
+        (defense.armor_type, defense.armor)
+    });
+
+    let multiplier = damage_table.multiplier(damage_type, armor_type);
+    if damage <= 0 || multiplier <= 0. {
+        return 0;
+    }
+
+    let armor_reduction = if armor >= 0 {
+        let armor = armor as f32 * ARMOR_REDUCTION_PER_POINT;
+        armor / (1. + armor)
+    } else {
+        // Negative armor increases the damage taken.
+        -(1. - (1. - ARMOR_REDUCTION_PER_POINT).powi(-armor))
+    };
+
+    ((damage as f32 * multiplier * (1. - armor_reduction)).round() as i32).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashMap;
+
+    use super::*;
+
+    fn damage_table() -> DamageTable {
+        DamageTable::from(HashMap::from([(
+            DamageType::Siege,
+            HashMap::from([(ArmorType::Fortified, 1.5), (ArmorType::Light, 0.)]),
+        )]))
+    }
+
+    fn defense(armor_type: ArmorType, armor: i32) -> Defense {
+        Defense { armor_type, armor }
+    }
+
+    #[test]
+    fn full_damage_without_defense() {
+        assert_eq!(
+            calculate_damage(10, DamageType::Normal, None, &damage_table()),
+            10
+        );
+    }
+
+    #[test]
+    fn damage_table_multiplies_damage() {
+        let fortified = defense(ArmorType::Fortified, 0);
+        assert_eq!(
+            calculate_damage(10, DamageType::Siege, Some(&fortified), &damage_table()),
+            15
+        );
+    }
+
+    #[test]
+    fn armor_reduces_damage() {
+        // 5 armor reduces damage by 0.3 / 1.3, about 23%.
+        let medium = defense(ArmorType::Medium, 5);
+        assert_eq!(
+            calculate_damage(10, DamageType::Normal, Some(&medium), &damage_table()),
+            8
+        );
+    }
+
+    #[test]
+    fn negative_armor_increases_damage() {
+        // -2 armor increases damage by 1 - 0.94^2, about 12%.
+        let medium = defense(ArmorType::Medium, -2);
+        assert_eq!(
+            calculate_damage(10, DamageType::Normal, Some(&medium), &damage_table()),
+            11
+        );
+    }
+
+    #[test]
+    fn attacks_deal_at_least_one_damage() {
+        let heavy = defense(ArmorType::Heavy, 20);
+        assert_eq!(
+            calculate_damage(1, DamageType::Normal, Some(&heavy), &damage_table()),
+            1
+        );
+    }
+
+    #[test]
+    fn no_damage_without_effect_or_damage() {
+        let light = defense(ArmorType::Light, 0);
+        assert_eq!(
+            calculate_damage(10, DamageType::Siege, Some(&light), &damage_table()),
+            0
+        );
+        assert_eq!(
+            calculate_damage(0, DamageType::Normal, None, &damage_table()),
+            0
+        );
+    }
+}
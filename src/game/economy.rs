@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::game::damage::{apply_damage, DeathEvent};
+use crate::game::health::check_death;
+use crate::game::teams::Team;
+use crate::resources::GameMode;
+
+/*
+Handles the gold of each team. Gold is earned as passive income and by killing enemies,
+and spent on buildings.
+*/
+
+// --- Plugin ---
+
+pub struct EconomyPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for EconomyPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Treasuries>()
+            .init_resource::<GameMode>()
+            .insert_resource(IncomeTimer(Timer::from_seconds(
+                INCOME_INTERVAL,
+                TimerMode::Repeating,
+            )))
+            .add_systems(OnEnter(self.state.clone()), setup_treasuries)
+            .add_systems(Update, pay_income.run_if(in_state(self.state.clone())))
+            .add_systems(
+                PostUpdate,
+                pay_bounties
+                    .after(apply_damage)
+                    .before(check_death)
+                    .run_if(in_state(self.state.clone())),
+            );
+    }
+}
+
+// --- Constants ---
+
+const STARTING_GOLD: u32 = 150;
+const STARTING_INCOME: u32 = 10;
+/// Seconds between each income payout.
+const INCOME_INTERVAL: f32 = 5.;
+
+// --- Resources ---
+
+#[derive(Debug, Clone, Reflect)]
+pub struct Treasury {
+    pub gold: u32,
+    /// Gold paid out every income interval.
+    pub income: u32,
+}
+
+/// The treasury of every team taking part in the match.
+#[derive(Resource, Default, Reflect)]
+pub struct Treasuries(pub HashMap<Team, Treasury>);
+
+impl Treasuries {
+    pub fn gold(&self, team: Team) -> u32 {
+        self.0.get(&team).map_or(0, |treasury| treasury.gold)
+    }
+
+    pub fn can_afford(&self, team: Team, cost: u32) -> bool {
+        self.gold(team) >= cost
+    }
+
+    /// Withdraws the cost from the team's treasury. Returns false and leaves the treasury
+    /// untouched, if the team cannot afford it.
+    pub fn try_spend(&mut self, team: Team, cost: u32) -> bool {
+        match self.0.get_mut(&team) {
+            Some(treasury) if treasury.gold >= cost => {
+                treasury.gold -= cost;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn deposit(&mut self, team: Team, amount: u32) {
+        if let Some(treasury) = self.0.get_mut(&team) {
+            treasury.gold += amount;
+        }
+    }
+}
+
+#[derive(Resource)]
+struct IncomeTimer(Timer);
+
+// --- Components ---
+
+/// Gold given to the team that kills the entity, before the game mode's multiplier.
+#[derive(Component, Reflect)]
+pub struct Bounty(pub u32);
+
+// --- Systems ---
+
+/// Gives every playing team a fresh treasury at the start of a match.
+fn setup_treasuries(mut treasuries: ResMut<Treasuries>, mut income_timer: ResMut<IncomeTimer>) {
+    treasuries.0.clear();
+    for team in [Team::Red, Team::Blue] {
+        treasuries.0.insert(
+            team,
+            Treasury {
+                gold: STARTING_GOLD,
+                income: STARTING_INCOME,
+            },
+        );
+    }
+    income_timer.0.reset();
+}
+
+fn pay_income(
+    mut treasuries: ResMut<Treasuries>,
+    mut income_timer: ResMut<IncomeTimer>,
+    time: Res<Time>,
+) {
+    if !income_timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for treasury in treasuries.0.values_mut() {
+        treasury.gold += treasury.income;
+    }
+}
+
+/// Rewards the team that dealt the killing blow. Should run before the dead are despawned.
+fn pay_bounties(
+    mut ev_death: EventReader<DeathEvent>,
+    bounty_query: Query<&Bounty>,
+    mut treasuries: ResMut<Treasuries>,
+    game_mode: Res<GameMode>,
+) {
+    for event in ev_death.read() {
+        let Some(killer) = event.killer else {
+            continue;
+        };
+        // No reward for friendly fire.
+        if killer.team == event.team {
+            continue;
+        }
+        let Ok(bounty) = bounty_query.get(event.entity) else {
+            continue;
+        };
+
+        let gold = (bounty.0 as f32 * game_mode.bounty_multiplier()).round() as u32;
+        if gold > 0 {
+            treasuries.deposit(killer.team, gold);
+            info!("Team {} was paid {} gold for a kill!", killer.team, gold);
+        }
+    }
+}
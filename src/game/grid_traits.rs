@@ -4,6 +4,9 @@ use bevy::prelude::{Vec2, Vec3};
 Contains helper functions related to grids, for instance snapping to the grid.
 */
 
+/// Buildings are placed on a grid with cells of this size. Matches the grid of the LDtk maps.
+pub const GRID_SIZE: f32 = 32.;
+
 // Define a trait for snapping to grid functionality
 pub trait SnapToGrid {
     fn snap_to_grid(self, grid_size: f32) -> Self;
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::game::attack::BodyRadius;
+use crate::game::health::Health;
+use crate::game::status_effects::StatusEffects;
+use crate::game::vision::InVision;
+
+/*
+Healing of damaged friendlies. Healers restore health to the most damaged friendly in range,
+so factions can field support units like priests and repair crews.
+*/
+
+// --- Plugin ---
+
+pub struct HealingPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for HealingPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HealEvent>().add_systems(
+            Update,
+            (heal_friendlies, apply_healing)
+                .chain()
+                .run_if(in_state(self.state.clone())),
+        );
+    }
+}
+
+// --- Components ---
+
+#[derive(Component, Debug, Reflect)]
+pub struct Healer {
+    pub heal_amount: i32,
+    /// Seconds between each heal.
+    pub cooldown: f32,
+    /// Measured from the edge of the healer's body to the edge of the target's body, like attack ranges.
+    pub heal_range: f32,
+    pub time_till_next_heal: Timer,
+}
+
+// --- Events ---
+
+/// Restores health to the target. Health never goes above the maximum health.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HealEvent {
+    /// The healer. Healing from auras has no single source.
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub amount: i32,
+}
+
+// --- Systems ---
+
+/// Heals the friendly in vision with the lowest health, among those damaged and in range.
+#[allow(clippy::type_complexity)]
+fn heal_friendlies(
+    mut healer_query: Query<(
+        Entity,
+        &mut Healer,
+        &InVision,
+        &Transform,
+        Option<&BodyRadius>,
+        Option<&StatusEffects>,
+    )>,
+    friendly_query: Query<(&Health, &Transform, Option<&BodyRadius>)>,
+    mut ev_heal: EventWriter<HealEvent>,
+    time: Res<Time>,
+) {
+    for (entity, mut healer, in_vision, transform, opt_body_radius, opt_status_effects) in
+        healer_query.iter_mut()
+    {
+        // Stunned healers can't heal.
+        if opt_status_effects.is_some_and(|status_effects| status_effects.is_stunned()) {
+            continue;
+        }
+
+        if !healer.time_till_next_heal.finished() {
+            healer.time_till_next_heal.tick(time.delta());
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let radius = opt_body_radius.map_or(0., |body_radius| body_radius.0);
+
+        let target = in_vision
+            .friendlies
+            .iter()
+            .filter(|friendly| **friendly != entity)
+            .filter_map(|friendly| {
+                let (health, friendly_transform, opt_friendly_body_radius) =
+                    friendly_query.get(*friendly).ok()?;
+                if health.health <= 0 || health.health >= health.max_health {
+                    return None;
+                }
+                let friendly_radius =
+                    opt_friendly_body_radius.map_or(0., |body_radius| body_radius.0);
+                let edge_distance = position.distance(friendly_transform.translation.truncate())
+                    - radius
+                    - friendly_radius;
+                (edge_distance <= healer.heal_range).then_some((*friendly, health.health))
+            })
+            .min_by_key(|(_, health)| *health);
+
+        // Wait for someone to heal. The heal is ready as soon as there is.
+        let Some((target, _)) = target else {
+            continue;
+        };
+
+        ev_heal.send(HealEvent {
+            source: Some(entity),
+            target,
+            amount: healer.heal_amount,
+        });
+
+        healer.time_till_next_heal =
+            Timer::new(Duration::from_secs_f32(healer.cooldown), TimerMode::Once);
+    }
+}
+
+fn apply_healing(mut ev_heal: EventReader<HealEvent>, mut query: Query<&mut Health>) {
+    for event in ev_heal.read() {
+        if let Ok(mut health) = query.get_mut(event.target) {
+            // The dead can't be healed.
+            if health.health <= 0 {
+                continue;
+            }
+            health.heal(event.amount);
+            debug!(
+                "{:?} healed {:?} for {:?}!",
+                event.source, event.target, event.amount
+            );
+        }
+    }
+}
@@ -3,7 +3,7 @@ use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::LdtkFields;
 use bevy_ecs_ldtk::EntityInstance;
 
-use crate::game::teams::Team;
+use crate::game::damage::DeathEvent;
 
 // --- Plugin ---
 
@@ -26,25 +26,38 @@ pub struct Health {
 }
 
 impl Health {
+    /// Current health is never above the maximum health.
+    pub fn new(health: i32, max_health: i32) -> Health {
+        Health {
+            health: health.min(max_health),
+            max_health,
+        }
+    }
+
     pub fn from_field(entity_instance: &EntityInstance) -> Health {
         let health = entity_instance
             .get_int_field("health")
             .expect("This entity should have a health field.");
-        Health {
-            health: *health,
-            max_health: *health,
-        }
+        Health::new(*health, *health)
+    }
+
+    /// Restores health up to the maximum health.
+    pub fn heal(&mut self, amount: i32) {
+        self.health = (self.health + amount.max(0)).min(self.max_health);
     }
 }
 
 // --- Systems ---
 
-/// Should run after attack_system.
-fn check_death(mut commands: Commands, query: Query<(Entity, &Health), With<Team>>) {
-    for (entity, health) in query.iter() {
-        if health.health <= 0 {
-            commands.entity(entity).despawn_recursive();
-            info!("{:?} died as health was depleted!", entity);
+/// Despawns the entities that died. Should run after the damage has been applied.
+pub fn check_death(mut commands: Commands, mut ev_death: EventReader<DeathEvent>) {
+    for event in ev_death.read() {
+        if let Some(entity_commands) = commands.get_entity(event.entity) {
+            entity_commands.despawn_recursive();
         }
+        info!(
+            "{:?} of team {} died as health was depleted! Killed by {:?}.",
+            event.entity, event.team, event.killer
+        );
     }
 }
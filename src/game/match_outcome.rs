@@ -0,0 +1,216 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::game::buildings::Castle;
+use crate::game::damage::apply_damage;
+use crate::game::health::{check_death, Health};
+use crate::game::teams::Team;
+use crate::game::{InGameTag, SimulationState};
+use crate::resources::{PlayerSettings, TeamSettings};
+use crate::styles::{HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON};
+use crate::AppState;
+
+/*
+Decides when a match is over and shows the results to the player.
+A team is defeated, when its last castle is destroyed.
+*/
+
+// --- Plugin ---
+
+pub struct MatchOutcomePlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for MatchOutcomePlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MatchEnded>()
+            .add_systems(
+                PostUpdate,
+                check_castles_destroyed
+                    .after(apply_damage)
+                    .before(check_death)
+                    .run_if(in_state(self.state.clone()))
+                    .run_if(not(in_state(SimulationState::Ended))),
+            )
+            .add_systems(
+                Update,
+                (
+                    end_match,
+                    spawn_results_screen,
+                    results_btn_interaction_handler,
+                    results_btn_action_handler,
+                )
+                    .run_if(in_state(self.state.clone())),
+            );
+    }
+}
+
+// --- Events ---
+
+/// Sent once, when only one team has castles left standing.
+/// The winner is `None` if the last castles of all teams fell at the same time.
+#[derive(Event)]
+pub struct MatchEnded {
+    pub winner: Option<Team>,
+}
+
+// --- Components ---
+
+#[derive(Component)]
+struct BtnBackToMainMenu;
+
+// --- Systems ---
+
+/// Should run after damage is applied, but before the dead castles are despawned.
+fn check_castles_destroyed(
+    castle_query: Query<(&Team, &Health), With<Castle>>,
+    mut ev_match_ended: EventWriter<MatchEnded>,
+) {
+    // Contains every team with a castle and whether it has at least one castle standing.
+    let mut teams_standing: HashMap<Team, bool> = HashMap::new();
+    for (team, health) in castle_query.iter() {
+        let standing = teams_standing.entry(*team).or_insert(false);
+        *standing |= health.health > 0;
+    }
+
+    // The match hasn't started properly, until the castles have been spawned.
+    if teams_standing.is_empty() || teams_standing.values().all(|standing| *standing) {
+        return;
+    }
+
+    let mut standing_teams = teams_standing
+        .iter()
+        .filter(|(_, standing)| **standing)
+        .map(|(team, _)| *team);
+    let winner = standing_teams.next();
+
+    // Several teams still standing means the match continues.
+    if standing_teams.next().is_some() {
+        return;
+    }
+
+    ev_match_ended.send(MatchEnded { winner });
+}
+
+fn end_match(
+    mut ev_match_ended: EventReader<MatchEnded>,
+    mut next_state: ResMut<NextState<SimulationState>>,
+) {
+    for ev in ev_match_ended.read() {
+        match ev.winner {
+            Some(winner) => info!("The match has ended. {} won!", winner),
+            None => info!("The match has ended in a draw!"),
+        }
+        next_state.set(SimulationState::Ended);
+    }
+}
+
+fn spawn_results_screen(
+    mut commands: Commands,
+    mut ev_match_ended: EventReader<MatchEnded>,
+    player_settings: Res<PlayerSettings>,
+    team_settings: Res<TeamSettings>,
+) {
+    for ev in ev_match_ended.read() {
+        let (title, title_color) = match ev.winner {
+            Some(winner) if winner == player_settings.team => ("Victory!", Color::GOLD),
+            Some(_) => ("Defeat!", Color::RED),
+            None => ("Draw!", Color::WHITE),
+        };
+
+        commands
+            .spawn((
+                InGameTag, // Adding this tag, means it will be cleaned up, when exiting the "Game" app state.
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.),
+                        height: Val::Percent(100.),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(24.),
+                        ..Default::default()
+                    },
+                    background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.7)),
+                    z_index: ZIndex::Global(10),
+                    ..Default::default()
+                },
+            ))
+            .with_children(|builder| {
+                builder.spawn(TextBundle::from_section(
+                    title,
+                    TextStyle {
+                        font_size: 60.,
+                        color: title_color,
+                        ..Default::default()
+                    },
+                ));
+                if let Some(winner) = ev.winner {
+                    builder.spawn(TextBundle::from_section(
+                        format!("Team {} destroyed all enemy castles.", winner),
+                        TextStyle {
+                            font_size: 24.,
+                            color: team_settings.color(winner),
+                            ..Default::default()
+                        },
+                    ));
+                }
+                builder
+                    .spawn((
+                        BtnBackToMainMenu,
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(300.),
+                                border: UiRect::all(Val::Px(3.)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            border_color: BorderColor(Color::WHITE),
+                            background_color: BackgroundColor(NORMAL_BUTTON),
+                            ..Default::default()
+                        },
+                    ))
+                    .with_children(|btn_builder| {
+                        btn_builder.spawn(TextBundle::from_section(
+                            "Back to main menu",
+                            TextStyle {
+                                font_size: 30.,
+                                color: Color::WHITE,
+                                ..Default::default()
+                            },
+                        ));
+                    });
+            });
+    }
+}
+
+fn results_btn_action_handler(
+    query: Query<&Interaction, (Changed<Interaction>, With<BtnBackToMainMenu>)>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for interaction in query.iter() {
+        if *interaction == Interaction::Pressed {
+            next_state.set(AppState::MainMenu);
+        }
+    }
+}
+
+fn results_btn_interaction_handler(
+    mut query: Query<(&Interaction, &mut BackgroundColor), With<BtnBackToMainMenu>>,
+) {
+    for (interaction, mut bg_colour) in query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *bg_colour = PRESSED_BUTTON.into();
+            }
+            Interaction::Hovered => {
+                *bg_colour = HOVERED_BUTTON.into();
+            }
+            Interaction::None => {
+                *bg_colour = NORMAL_BUTTON.into();
+            }
+        }
+    }
+}
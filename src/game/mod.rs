@@ -3,37 +3,71 @@ use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 // use
+use ai::AiPlugin;
 use attack::AttackPlugin;
+use auras::AuraPlugin;
+use building_removal::BuildingRemovalPlugin;
 use building_spawning::BuildingSpawningPlugin;
+use building_upgrades::BuildingUpgradePlugin;
 use camera::CameraPlugin;
 use castle_fight_ldtk::CastleFightLdtkPlugin;
+use construction::ConstructionPlugin;
+use damage::DamagePlugin;
+use economy::EconomyPlugin;
+use healing::HealingPlugin;
 use health::HealthPlugin;
+use match_outcome::MatchOutcomePlugin;
 use movement::MovementPlugin;
+use projectiles::ProjectilePlugin;
+use research::ResearchPlugin;
 use resources::ResourcesPlugin;
+use selection::SelectionPlugin;
+use splash::SplashPlugin;
+use status_effects::StatusEffectPlugin;
+use supply::SupplyPlugin;
 use systems::*;
+use targeting::TargetingPlugin;
 use unit_spawning::UnitSpawningPlugin;
+use veterancy::VeterancyPlugin;
 use vision::VisionPlugin;
-use waypoints::WaypointPlugin;
+use waypoints::{clear_waypoint_map, WaypointPlugin};
 
 use crate::game::ui::UiPlugin;
 use crate::AppState;
 
 //mod
-mod attack;
+mod ai;
+pub mod attack;
+pub mod auras;
+pub mod building_removal;
 mod building_spawning;
+pub mod building_upgrades;
 mod buildings;
 mod camera;
 mod castle_fight_ldtk;
+pub mod construction;
+pub mod damage;
+pub mod economy;
 mod grid_traits;
+pub mod healing;
 pub mod health;
+pub mod match_outcome;
 pub mod movement;
+pub mod projectiles;
+pub mod research;
 mod resources;
+mod selection;
 mod spawning;
+pub mod splash;
+pub mod status_effects;
+pub mod supply;
 mod systems;
+pub mod targeting;
 pub mod teams;
 mod ui;
-mod unit_spawning;
+pub mod unit_spawning;
 mod units;
+pub mod veterancy;
 pub mod vision;
 pub mod waypoints;
 
@@ -71,6 +105,59 @@ impl Plugin for GamePlugin {
             UnitSpawningPlugin {
                 state: AppState::Game,
             },
+            EconomyPlugin {
+                state: AppState::Game,
+            },
+            MatchOutcomePlugin {
+                state: AppState::Game,
+            },
+            AiPlugin {
+                state: AppState::Game,
+            },
+            TargetingPlugin {
+                state: AppState::Game,
+            },
+        ))
+        .add_plugins((
+            DamagePlugin {
+                state: AppState::Game,
+            },
+            ProjectilePlugin {
+                state: AppState::Game,
+            },
+            SplashPlugin {
+                state: AppState::Game,
+            },
+            StatusEffectPlugin {
+                state: AppState::Game,
+            },
+            HealingPlugin {
+                state: AppState::Game,
+            },
+            AuraPlugin {
+                state: AppState::Game,
+            },
+            VeterancyPlugin {
+                state: AppState::Game,
+            },
+            ResearchPlugin {
+                state: AppState::Game,
+            },
+            SelectionPlugin {
+                state: AppState::Game,
+            },
+            BuildingUpgradePlugin {
+                state: AppState::Game,
+            },
+            SupplyPlugin {
+                state: AppState::Game,
+            },
+            BuildingRemovalPlugin {
+                state: AppState::Game,
+            },
+            ConstructionPlugin {
+                state: AppState::Game,
+            },
         ))
         // Physics plugins.
         .add_plugins((
@@ -85,11 +172,15 @@ impl Plugin for GamePlugin {
         .insert_resource(LevelSelection::index(0))
         //State Transitions
         .add_systems(OnEnter(SimulationState::Paused), pause_simulation)
+        .add_systems(OnEnter(SimulationState::Ended), pause_simulation)
         .add_systems(OnEnter(SimulationState::Running), unpause_simulation)
         // Systems
         .add_systems(Update, toggle_simulation.run_if(in_state(AppState::Game)))
         .add_systems(OnEnter(AppState::Game), setup)
-        .add_systems(OnExit(AppState::Game), cleanup_after_game);
+        .add_systems(
+            OnExit(AppState::Game),
+            (cleanup_after_game, reset_simulation, clear_waypoint_map),
+        );
     }
 }
 
@@ -100,6 +191,8 @@ pub enum SimulationState {
     #[default]
     Running,
     Paused,
+    /// The match is over and the simulation is frozen, until leaving the game.
+    Ended,
 }
 
 // --- Components ---
@@ -1,4 +1,5 @@
-use crate::game::attack::AttackTarget;
+use crate::game::attack::{AttackStats, AttackTarget, BodyRadius};
+use crate::game::status_effects::StatusEffects;
 use crate::game::waypoints::Waypoint;
 use bevy::prelude::*;
 
@@ -31,6 +32,11 @@ pub struct MovementSpeed(pub f32);
 #[derive(Component)]
 pub struct OpponentFollower;
 
+/// Entities that never move, like buildings.
+/// Stationary attackers only choose targets within their attack range.
+#[derive(Component, Default)]
+pub struct Stationary;
+
 #[derive(Component, Reflect)]
 pub struct WaypointFollower {
     pub waypoint: Entity,
@@ -47,16 +53,31 @@ pub struct MoveToPoint(pub Vec2);
 
 /// Moves towards MoveToPoint (which are on-time-use and will be removed after use).
 /// Should always run after systems inserting MoveToPoint components on entities.
+#[allow(clippy::type_complexity)]
 fn move_towards_point(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Transform, &MoveToPoint, &MovementSpeed)>,
+    mut query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &MoveToPoint,
+            &MovementSpeed,
+            Option<&StatusEffects>,
+        ),
+        Without<Stationary>,
+    >,
     time: Res<Time>,
 ) {
-    for (entity, mut transform, move_to_point, movement_speed) in query.iter_mut() {
+    for (entity, mut transform, move_to_point, movement_speed, opt_status_effects) in
+        query.iter_mut()
+    {
         let direction = move_to_point.0 - transform.translation.xy();
+        // Slows and stuns scale the movement speed.
+        let speed = movement_speed.0
+            * opt_status_effects.map_or(1., |status_effects| status_effects.speed_multiplier());
         // Move towards the target.
-        let move_direction = direction.normalize();
-        let move_amount = (move_direction.xy() * movement_speed.0 * time.delta_seconds())
+        let move_direction = direction.normalize_or_zero();
+        let move_amount = (move_direction.xy() * speed * time.delta_seconds())
             // .clamp_length_max(direction.length())
             .extend(0.);
         transform.translation += move_amount;
@@ -66,14 +87,41 @@ fn move_towards_point(
 }
 
 /// If entity is an opponent follower and has an attack target, it should become the move target.
+/// Once the attack target is within attack range, the entity stops and holds its position.
+#[allow(clippy::type_complexity)]
 fn sync_attack_move_target(
     mut commands: Commands,
     mut attack_move_target_query: Query<
-        (Entity, Option<&mut MoveTarget>, &AttackTarget),
-        With<OpponentFollower>,
+        (
+            Entity,
+            Option<&mut MoveTarget>,
+            &AttackTarget,
+            &AttackStats,
+            &Transform,
+            Option<&BodyRadius>,
+        ),
+        (With<OpponentFollower>, Without<Stationary>),
     >,
+    target_query: Query<(&Transform, Option<&BodyRadius>)>,
 ) {
-    for (entity, opt_move_target, attack_target) in attack_move_target_query.iter_mut() {
+    for (entity, opt_move_target, attack_target, attack_stats, transform, opt_body_radius) in
+        attack_move_target_query.iter_mut()
+    {
+        if let Ok((target_transform, opt_target_body_radius)) = target_query.get(attack_target.0) {
+            if attack_stats.in_range(
+                (transform.translation.truncate(), opt_body_radius),
+                (
+                    target_transform.translation.truncate(),
+                    opt_target_body_radius,
+                ),
+            ) {
+                if opt_move_target.is_some() {
+                    commands.entity(entity).remove::<MoveTarget>();
+                }
+                continue;
+            }
+        }
+
         match opt_move_target {
             Some(mut move_target) => {
                 if move_target.0 != attack_target.0 {
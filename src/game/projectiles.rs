@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+
+use crate::game::attack::BodyRadius;
+use crate::game::damage::{DamageEvent, DamageKind, DamageSource, DamageType};
+use crate::game::health::Health;
+use crate::game::splash::{SplashDamage, SplashEvent};
+use crate::game::status_effects::{OnHitEffects, StatusEffectEvent};
+use crate::game::InGameTag;
+
+/*
+Projectiles fired by ranged attackers. They deal their damage on impact instead of instantly,
+so projectiles that don't home in on their target can miss, if the target moves.
+*/
+
+// --- Plugin ---
+
+pub struct ProjectilePlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for ProjectilePlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            move_projectiles.run_if(in_state(self.state.clone())),
+        );
+    }
+}
+
+// --- Constants ---
+
+/// How far from the edge of the target's body a projectile can land and still hit it.
+const HIT_TOLERANCE: f32 = 8.;
+
+// --- Components ---
+
+/// Makes an attacker fire projectiles instead of dealing damage instantly.
+#[derive(Component)]
+pub struct ProjectileLauncher {
+    pub speed: f32,
+    /// Projectiles without a sprite are drawn as a rectangle in the team color.
+    pub sprite: Option<Handle<Image>>,
+    pub size: Option<Vec2>,
+    /// Homing projectiles follow their target, while others fly to where the target was.
+    pub homing: bool,
+    /// How high the projectile arcs halfway to the target.
+    pub arc_height: f32,
+}
+
+#[derive(Component)]
+pub struct Projectile {
+    /// The attacker that fired it.
+    pub source: DamageSource,
+    pub target: Entity,
+    pub damage: i32,
+    pub damage_type: DamageType,
+    pub speed: f32,
+    pub homing: bool,
+    pub arc_height: f32,
+    /// Where the projectile was fired from.
+    pub origin: Vec2,
+    /// Where the projectile will land. Follows the target for homing projectiles.
+    pub destination: Vec2,
+    /// The position of the projectile on the ground. The sprite is drawn above it while arcing.
+    pub ground_position: Vec2,
+    /// Splash damage is dealt where the projectile lands, even if it misses the target.
+    pub splash: Option<SplashDamage>,
+    /// Applied to the target, if the projectile hits it.
+    pub on_hit_effects: Option<OnHitEffects>,
+}
+
+// --- Systems ---
+
+#[allow(clippy::type_complexity)]
+fn move_projectiles(
+    mut commands: Commands,
+    mut projectile_query: Query<(Entity, &mut Projectile, &mut Transform)>,
+    target_query: Query<(&Transform, Option<&BodyRadius>), (With<Health>, Without<Projectile>)>,
+    mut ev_damage: EventWriter<DamageEvent>,
+    mut ev_splash: EventWriter<SplashEvent>,
+    mut ev_status_effect: EventWriter<StatusEffectEvent>,
+    time: Res<Time>,
+) {
+    for (entity, mut projectile, mut transform) in projectile_query.iter_mut() {
+        if projectile.homing {
+            if let Ok((target_transform, _)) = target_query.get(projectile.target) {
+                projectile.destination = target_transform.translation.truncate();
+            }
+        }
+
+        let step = projectile.speed * time.delta_seconds();
+        let to_destination = projectile.destination - projectile.ground_position;
+
+        // Impact, when the destination will be reached this frame.
+        if to_destination.length() <= step {
+            let mut hit_target = false;
+            if let Ok((target_transform, opt_body_radius)) = target_query.get(projectile.target) {
+                let target_radius = opt_body_radius.map_or(0., |body_radius| body_radius.0);
+                let distance = target_transform
+                    .translation
+                    .truncate()
+                    .distance(projectile.destination);
+                if distance <= target_radius + HIT_TOLERANCE {
+                    ev_damage.send(DamageEvent {
+                        source: Some(projectile.source),
+                        target: projectile.target,
+                        amount: projectile.damage,
+                        kind: DamageKind::Attack(projectile.damage_type),
+                    });
+                    hit_target = true;
+
+                    if let Some(on_hit_effects) = &projectile.on_hit_effects {
+                        ev_status_effect.send(StatusEffectEvent {
+                            target: projectile.target,
+                            source: projectile.source,
+                            effects: on_hit_effects.0.clone(),
+                        });
+                    }
+                }
+            }
+
+            if let Some(splash) = projectile.splash {
+                ev_splash.send(SplashEvent {
+                    position: projectile.destination,
+                    source: projectile.source,
+                    damage: projectile.damage,
+                    damage_type: projectile.damage_type,
+                    splash,
+                    primary_target: hit_target.then_some(projectile.target),
+                });
+            }
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        projectile.ground_position += to_destination.normalize() * step;
+
+        let previous_position = transform.translation.truncate();
+        let position = projectile.ground_position + Vec2::new(0., arc_offset(&projectile));
+        let direction = position - previous_position;
+        transform.translation = position.extend(transform.translation.z);
+        if direction != Vec2::ZERO {
+            transform.rotation = Quat::from_rotation_z(direction.y.atan2(direction.x));
+        }
+    }
+}
+
+// --- Helper functions ---
+
+/// Helper function to spawn a projectile. This is not a system.
+pub fn spawn_projectile(
+    commands: &mut Commands,
+    launcher: &ProjectileLauncher,
+    projectile: Projectile,
+    color: Color,
+) {
+    let origin = projectile.origin;
+    let (texture, color) = match &launcher.sprite {
+        Some(sprite) => (sprite.clone(), Color::WHITE),
+        None => (Handle::default(), color),
+    };
+
+    commands.spawn((
+        InGameTag,
+        Name::new("Projectile"),
+        projectile,
+        SpriteBundle {
+            texture,
+            sprite: Sprite {
+                color,
+                custom_size: launcher.size,
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(origin.x, origin.y, 20.),
+            ..Default::default()
+        },
+    ));
+}
+
+/// How high above the ground the projectile is, based on how far it has travelled.
+fn arc_offset(projectile: &Projectile) -> f32 {
+    let total_distance = projectile.origin.distance(projectile.destination);
+    if total_distance <= 0. {
+        return 0.;
+    }
+    let remaining_distance = projectile.ground_position.distance(projectile.destination);
+    let progress = (1. - remaining_distance / total_distance).clamp(0., 1.);
+    projectile.arc_height * 4. * progress * (1. - progress)
+}
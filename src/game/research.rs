@@ -0,0 +1,457 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use serde::Deserialize;
+
+use crate::game::attack::AttackStats;
+use crate::game::construction::UnderConstruction;
+use crate::game::damage::{apply_damage, DeathEvent, Defense};
+use crate::game::economy::Treasuries;
+use crate::game::health::{check_death, Health};
+use crate::game::movement::MovementSpeed;
+use crate::game::spawning::BlueprintId;
+use crate::game::teams::Team;
+use crate::resources::TeamSettings;
+
+/*
+Upgrades researched at buildings, which improve the stats of units and buildings of a faction.
+A finished upgrade applies to the targets the team already has, and to all targets spawned afterwards.
+Upgrades are paid when queued, and refunded if the building is lost before they are researched.
+*/
+
+// --- Plugin ---
+
+pub struct ResearchPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for ResearchPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Research>()
+            .add_event::<ResearchRequestEvent>()
+            .add_event::<ResearchRefusedEvent>()
+            .add_event::<UpgradeResearchedEvent>()
+            .add_systems(OnEnter(self.state.clone()), reset_research)
+            .add_systems(
+                Update,
+                (
+                    queue_research,
+                    progress_research,
+                    upgrade_existing_targets,
+                    upgrade_spawned_targets,
+                )
+                    .chain()
+                    .run_if(in_state(self.state.clone())),
+            )
+            .add_systems(
+                PostUpdate,
+                refund_research_of_destroyed_buildings
+                    .after(apply_damage)
+                    .before(check_death)
+                    .run_if(in_state(self.state.clone())),
+            );
+    }
+}
+
+// --- Enums ---
+
+/// How an upgrade changes the stats of its targets. Targets without the stat are left alone.
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Reflect)]
+pub enum UpgradeEffect {
+    /// Adds attack damage.
+    Damage(i32),
+    /// Increases attack speed by a fraction, so 0.2 is +20% attack speed.
+    AttackSpeed(f32),
+    /// Adds attack range.
+    AttackRange(f32),
+    /// Adds maximum health. The current health is raised by the same amount.
+    MaxHealth(i32),
+    /// Adds armor.
+    Armor(i32),
+    /// Adds movement speed.
+    MovementSpeed(f32),
+}
+
+pub enum ResearchRefusal {
+    NotEnoughGold { cost: u32, gold: u32 },
+    MissingPrerequisite { upgrade: String },
+    AlreadyResearched,
+    AlreadyQueued,
+}
+
+impl fmt::Display for ResearchRefusal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ResearchRefusal::NotEnoughGold { cost, gold } => {
+                write!(f, "Not enough gold! Costs {} but you have {}.", cost, gold)
+            }
+            ResearchRefusal::MissingPrerequisite { upgrade } => {
+                write!(f, "{} must be researched first.", upgrade)
+            }
+            ResearchRefusal::AlreadyResearched => write!(f, "Already researched."),
+            ResearchRefusal::AlreadyQueued => write!(f, "Already being researched."),
+        }
+    }
+}
+
+// --- Types ---
+
+/// An upgrade a faction can research. Defined in the faction files.
+#[derive(Deserialize, Clone, PartialEq, Debug, Reflect)]
+pub struct UpgradeBlueprint {
+    pub id: String,
+    pub name: String,
+    /// Gold it costs to research the upgrade. Paid when it is queued.
+    pub cost: u32,
+    /// Seconds it takes to research the upgrade.
+    pub research_time: f32,
+    /// The ID of the building that researches the upgrade.
+    pub researched_at: String,
+    /// IDs of the upgrades that must be researched first.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// IDs of the units and buildings that are upgraded.
+    pub targets: Vec<String>,
+    pub effects: Vec<UpgradeEffect>,
+}
+
+// --- Resources ---
+
+/// The IDs of the upgrades each team has researched.
+#[derive(Resource, Default)]
+pub struct Research(pub HashMap<Team, HashSet<String>>);
+
+impl Research {
+    pub fn is_researched(&self, team: Team, upgrade_id: &str) -> bool {
+        self.0
+            .get(&team)
+            .is_some_and(|researched| researched.contains(upgrade_id))
+    }
+}
+
+// --- Components ---
+
+/// Upgrades waiting to be researched at a building. Only the first upgrade is researched at a time.
+#[derive(Component, Default)]
+pub struct ResearchQueue {
+    pub queue: Vec<UpgradeBlueprint>,
+    /// Progress of the first upgrade in the queue.
+    pub progress: Timer,
+}
+
+impl ResearchQueue {
+    fn push(&mut self, upgrade: UpgradeBlueprint) {
+        if self.queue.is_empty() {
+            self.progress = Timer::from_seconds(upgrade.research_time, TimerMode::Once);
+        }
+        self.queue.push(upgrade);
+    }
+
+    fn contains(&self, upgrade_id: &str) -> bool {
+        self.queue.iter().any(|upgrade| upgrade.id == upgrade_id)
+    }
+
+    /// The gold paid for the queued upgrades, including the one being researched.
+    pub fn queued_cost(&self) -> u32 {
+        self.queue.iter().map(|upgrade| upgrade.cost).sum()
+    }
+}
+
+// --- Events ---
+
+/// Asks a building to queue an upgrade, if its team can afford it and has the prerequisites.
+#[derive(Event)]
+pub struct ResearchRequestEvent {
+    pub building: Entity,
+    pub upgrade_id: String,
+}
+
+/// Sent when a team tried to queue an upgrade, but wasn't allowed to.
+#[derive(Event)]
+pub struct ResearchRefusedEvent {
+    pub team: Team,
+    pub reason: ResearchRefusal,
+}
+
+#[derive(Event)]
+pub struct UpgradeResearchedEvent {
+    pub team: Team,
+    pub upgrade: UpgradeBlueprint,
+}
+
+// --- Systems ---
+
+fn reset_research(mut research: ResMut<Research>) {
+    research.0.clear();
+}
+
+fn queue_research(
+    mut ev_research_request: EventReader<ResearchRequestEvent>,
+    mut ev_research_refused: EventWriter<ResearchRefusedEvent>,
+    mut query: Query<(&Team, &BlueprintId, &mut ResearchQueue)>,
+    research: Res<Research>,
+    mut treasuries: ResMut<Treasuries>,
+    team_settings: Res<TeamSettings>,
+) {
+    for ev in ev_research_request.read() {
+        let Ok((team, blueprint_id, _)) = query.get(ev.building) else {
+            continue;
+        };
+        let team = *team;
+        let Some(faction) = team_settings.faction(team) else {
+            continue;
+        };
+        let Some(upgrade) = faction.upgrades.get(&ev.upgrade_id) else {
+            error!(
+                "Upgrade {:?} doesn't exist for team {}.",
+                ev.upgrade_id, team
+            );
+            continue;
+        };
+        if upgrade.researched_at != blueprint_id.0 {
+            error!(
+                "Upgrade {:?} can't be researched at {:?}.",
+                upgrade.id, blueprint_id.0
+            );
+            continue;
+        }
+
+        let mut refuse = |reason: ResearchRefusal| {
+            ev_research_refused.send(ResearchRefusedEvent { team, reason });
+        };
+
+        if research.is_researched(team, &upgrade.id) {
+            refuse(ResearchRefusal::AlreadyResearched);
+            continue;
+        }
+        // The same upgrade can only be researched at one building at a time.
+        if query
+            .iter()
+            .any(|(other_team, _, queue)| *other_team == team && queue.contains(&upgrade.id))
+        {
+            refuse(ResearchRefusal::AlreadyQueued);
+            continue;
+        }
+        if let Some(missing) = upgrade
+            .requires
+            .iter()
+            .find(|required| !research.is_researched(team, required))
+        {
+            refuse(ResearchRefusal::MissingPrerequisite {
+                upgrade: faction
+                    .upgrades
+                    .get(missing)
+                    .map_or(missing.clone(), |required| required.name.clone()),
+            });
+            continue;
+        }
+        if !treasuries.try_spend(team, upgrade.cost) {
+            refuse(ResearchRefusal::NotEnoughGold {
+                cost: upgrade.cost,
+                gold: treasuries.gold(team),
+            });
+            continue;
+        }
+
+        if let Ok((_, _, mut research_queue)) = query.get_mut(ev.building) {
+            research_queue.push(upgrade.clone());
+        }
+    }
+}
+
+fn progress_research(
+    // Research can be queued at buildings under construction, but only starts once they are constructed.
+    mut query: Query<(&Team, &mut ResearchQueue), Without<UnderConstruction>>,
+    mut research: ResMut<Research>,
+    mut ev_upgrade_researched: EventWriter<UpgradeResearchedEvent>,
+    time: Res<Time>,
+) {
+    for (team, mut research_queue) in query.iter_mut() {
+        if research_queue.queue.is_empty() {
+            continue;
+        }
+        // Ticking only changes the progress, so it shouldn't count as a change to the queue.
+        if !research_queue
+            .bypass_change_detection()
+            .progress
+            .tick(time.delta())
+            .finished()
+        {
+            continue;
+        }
+
+        let upgrade = research_queue.queue.remove(0);
+        if let Some(next_upgrade) = research_queue.queue.first() {
+            research_queue.progress =
+                Timer::from_seconds(next_upgrade.research_time, TimerMode::Once);
+        }
+
+        research
+            .0
+            .entry(*team)
+            .or_default()
+            .insert(upgrade.id.clone());
+        info!("Team {} researched {}!", team, upgrade.name);
+        ev_upgrade_researched.send(UpgradeResearchedEvent {
+            team: *team,
+            upgrade,
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn upgrade_existing_targets(
+    mut ev_upgrade_researched: EventReader<UpgradeResearchedEvent>,
+    mut query: Query<(
+        &Team,
+        &BlueprintId,
+        Option<&mut Health>,
+        Option<&mut AttackStats>,
+        Option<&mut Defense>,
+        Option<&mut MovementSpeed>,
+    )>,
+) {
+    for ev in ev_upgrade_researched.read() {
+        for (
+            team,
+            blueprint_id,
+            mut opt_health,
+            mut opt_attack_stats,
+            mut opt_defense,
+            mut opt_movement_speed,
+        ) in query.iter_mut()
+        {
+            if *team != ev.team || !ev.upgrade.targets.contains(&blueprint_id.0) {
+                continue;
+            }
+            for effect in ev.upgrade.effects.iter() {
+                apply_upgrade_effect(
+                    *effect,
+                    opt_health.as_deref_mut(),
+                    opt_attack_stats.as_deref_mut(),
+                    opt_defense.as_deref_mut(),
+                    opt_movement_speed.as_deref_mut(),
+                );
+            }
+        }
+    }
+}
+
+/// Gives newly spawned units and buildings the upgrades their team has already researched.
+#[allow(clippy::type_complexity)]
+fn upgrade_spawned_targets(
+    mut query: Query<
+        (
+            &Team,
+            &BlueprintId,
+            Option<&mut Health>,
+            Option<&mut AttackStats>,
+            Option<&mut Defense>,
+            Option<&mut MovementSpeed>,
+        ),
+        Added<BlueprintId>,
+    >,
+    research: Res<Research>,
+    team_settings: Res<TeamSettings>,
+) {
+    for (
+        team,
+        blueprint_id,
+        mut opt_health,
+        mut opt_attack_stats,
+        mut opt_defense,
+        mut opt_movement_speed,
+    ) in query.iter_mut()
+    {
+        let (Some(faction), Some(researched)) =
+            (team_settings.faction(*team), research.0.get(team))
+        else {
+            continue;
+        };
+
+        for upgrade_id in researched.iter() {
+            let Some(upgrade) = faction.upgrades.get(upgrade_id) else {
+                continue;
+            };
+            if !upgrade.targets.contains(&blueprint_id.0) {
+                continue;
+            }
+            for effect in upgrade.effects.iter() {
+                apply_upgrade_effect(
+                    *effect,
+                    opt_health.as_deref_mut(),
+                    opt_attack_stats.as_deref_mut(),
+                    opt_defense.as_deref_mut(),
+                    opt_movement_speed.as_deref_mut(),
+                );
+            }
+        }
+    }
+}
+
+/// Refunds the upgrades queued at buildings that died. Should run before the dead are despawned.
+fn refund_research_of_destroyed_buildings(
+    mut ev_death: EventReader<DeathEvent>,
+    query: Query<&ResearchQueue>,
+    mut treasuries: ResMut<Treasuries>,
+) {
+    for event in ev_death.read() {
+        let Ok(research_queue) = query.get(event.entity) else {
+            continue;
+        };
+        let refund = research_queue.queued_cost();
+        if refund > 0 {
+            treasuries.deposit(event.team, refund);
+            info!(
+                "Team {} was refunded {} gold for research lost with {:?}.",
+                event.team, refund, event.entity
+            );
+        }
+    }
+}
+
+// --- Helper functions ---
+
+fn apply_upgrade_effect(
+    effect: UpgradeEffect,
+    opt_health: Option<&mut Health>,
+    opt_attack_stats: Option<&mut AttackStats>,
+    opt_defense: Option<&mut Defense>,
+    opt_movement_speed: Option<&mut MovementSpeed>,
+) {
+    match effect {
+        UpgradeEffect::Damage(damage) => {
+            if let Some(attack_stats) = opt_attack_stats {
+                attack_stats.damage += damage;
+            }
+        }
+        UpgradeEffect::AttackSpeed(fraction) => {
+            if let Some(attack_stats) = opt_attack_stats {
+                attack_stats.attack_speed *= 1. + fraction;
+            }
+        }
+        UpgradeEffect::AttackRange(range) => {
+            if let Some(attack_stats) = opt_attack_stats {
+                attack_stats.attack_range += range;
+            }
+        }
+        UpgradeEffect::MaxHealth(max_health) => {
+            if let Some(health) = opt_health {
+                health.max_health += max_health;
+                health.health += max_health;
+            }
+        }
+        UpgradeEffect::Armor(armor) => {
+            if let Some(defense) = opt_defense {
+                defense.armor += armor;
+            }
+        }
+        UpgradeEffect::MovementSpeed(speed) => {
+            if let Some(movement_speed) = opt_movement_speed {
+                movement_speed.0 += speed;
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+
+use crate::game::attack::BodyRadius;
+use crate::game::buildings::{Building, BuildingGhost};
+use crate::game::resources::MousePosition;
+use crate::game::teams::Team;
+use crate::game::unit_spawning::RallyPoint;
+use crate::resources::PlayerSettings;
+
+/*
+Lets the player select one of their buildings by clicking on it, so the UI can show its info panel.
+The lane the selected building sends its units down is marked with a line.
+*/
+
+// --- Plugin ---
+
+pub struct SelectionPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for SelectionPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedBuilding>()
+            .add_systems(OnEnter(self.state.clone()), clear_selection)
+            .add_systems(
+                Update,
+                (
+                    select_building,
+                    clear_despawned_selection,
+                    draw_selected_rally_point,
+                )
+                    .chain()
+                    .run_if(in_state(self.state.clone())),
+            );
+    }
+}
+
+// --- Resources ---
+
+/// The building of the player's team, which is currently selected.
+#[derive(Resource, Default, PartialEq)]
+pub struct SelectedBuilding(pub Option<Entity>);
+
+// --- Systems ---
+
+fn clear_selection(mut selected_building: ResMut<SelectedBuilding>) {
+    selected_building.0 = None;
+}
+
+/// Left clicking selects the player's building under the cursor, or clears the selection.
+/// Right clicking clears the selection.
+fn select_building(
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    mouse_position: Res<MousePosition>,
+    player_settings: Res<PlayerSettings>,
+    building_query: Query<(Entity, &Transform, &Team, &BodyRadius), With<Building>>,
+    ghost_query: Query<(), With<BuildingGhost>>,
+    interaction_query: Query<&Interaction>,
+    mut selected_building: ResMut<SelectedBuilding>,
+) {
+    if mouse_button_input.just_pressed(MouseButton::Right) {
+        selected_building.0 = None;
+        return;
+    }
+    if !mouse_button_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    // Clicks are meant for placing the building or for the UI.
+    if !ghost_query.is_empty()
+        || interaction_query
+            .iter()
+            .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+
+    let cursor = Vec2::new(mouse_position.x, mouse_position.y);
+    let clicked_building = building_query
+        .iter()
+        .find(|(_, transform, team, body_radius)| {
+            **team == player_settings.team
+                && transform.translation.truncate().distance(cursor) <= body_radius.0
+        })
+        .map(|(entity, ..)| entity);
+    selected_building.set_if_neq(SelectedBuilding(clicked_building));
+}
+
+fn clear_despawned_selection(
+    mut selected_building: ResMut<SelectedBuilding>,
+    building_query: Query<(), With<Building>>,
+) {
+    if let Some(entity) = selected_building.0 {
+        if !building_query.contains(entity) {
+            selected_building.0 = None;
+        }
+    }
+}
+
+/// Draws a line from the selected building to the start of the lane its units are sent down.
+fn draw_selected_rally_point(
+    mut gizmos: Gizmos,
+    selected_building: Res<SelectedBuilding>,
+    building_query: Query<(&Transform, &RallyPoint)>,
+    waypoint_query: Query<&Transform>,
+) {
+    let Some((transform, rally_point)) = selected_building
+        .0
+        .and_then(|entity| building_query.get(entity).ok())
+    else {
+        return;
+    };
+    let Some(waypoint_transform) = rally_point
+        .0
+        .and_then(|waypoint| waypoint_query.get(waypoint).ok())
+    else {
+        return;
+    };
+
+    gizmos.line_2d(
+        transform.translation.truncate(),
+        waypoint_transform.translation.truncate(),
+        Color::YELLOW,
+    );
+}
@@ -3,26 +3,65 @@ use std::time::Duration;
 use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 
-use crate::game::attack::AttackStats;
+use crate::game::attack::{AttackStats, AttackTarget};
+use crate::game::auras::Auras;
+use crate::game::damage::Defense;
+use crate::game::healing::Healer;
 use crate::game::health::Health;
 use crate::game::movement::{MovementSpeed, OpponentFollower};
-use crate::game::unit_spawning::UnitSpawner;
+use crate::game::projectiles::ProjectileLauncher;
+use crate::game::splash::SplashDamage;
+use crate::game::status_effects::OnHitEffects;
+use crate::game::supply::SupplyProvider;
+use crate::game::targeting::{AttackCandidates, TargetingRules};
+use crate::game::unit_spawning::{SpawnEntry, UnitSpawner};
 use crate::game::vision::{InVision, Visible, VisionRange};
-use crate::load_game::load_factions::ComponentBlueprint;
-use crate::resources::PlayerSettings;
+use crate::load_game::load_factions::{ComponentBlueprint, FactionBlueprint};
+
+// --- Components ---
+
+/// The ID of the building or unit blueprint an entity was spawned from.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+pub struct BlueprintId(pub String);
+
+// --- Helper functions ---
+
+/// Removes everything `add_blueprint_components` can insert, so an entity can get the components of another blueprint.
+pub fn remove_blueprint_components(entity_commands: &mut EntityCommands) {
+    entity_commands
+        .remove::<(
+            Health,
+            Visible,
+            OpponentFollower,
+            MovementSpeed,
+            AttackStats,
+            AttackCandidates,
+            AttackTarget,
+            TargetingRules,
+        )>()
+        .remove::<(
+            Defense,
+            UnitSpawner,
+            ProjectileLauncher,
+            SplashDamage,
+            OnHitEffects,
+            Auras,
+            Healer,
+            VisionRange,
+            InVision,
+            SupplyProvider,
+        )>();
+}
 
 pub fn add_blueprint_components(
     entity_commands: &mut EntityCommands,
     component_blueprints: &[ComponentBlueprint],
-    player_settings: &Res<PlayerSettings>,
+    faction: &FactionBlueprint,
 ) {
     for component_blueprint in component_blueprints.iter() {
         match component_blueprint {
             ComponentBlueprint::Health { max_health, health } => {
-                entity_commands.insert(Health {
-                    health: *health,
-                    max_health: *max_health,
-                });
+                entity_commands.insert(Health::new(*health, *max_health));
             }
             ComponentBlueprint::Visible => {
                 entity_commands.insert(Visible);
@@ -35,24 +74,97 @@ pub fn add_blueprint_components(
             }
             ComponentBlueprint::AttackStats {
                 damage,
+                damage_type,
                 attack_speed,
                 attack_range,
             } => {
-                entity_commands.insert(AttackStats {
-                    damage: *damage,
-                    attack_speed: *attack_speed,
-                    attack_range: *attack_range as f32,
-                    time_till_next_attack: Timer::new(Duration::from_secs(0), TimerMode::Once),
+                entity_commands.insert(AttackStats::new(
+                    *damage,
+                    *damage_type,
+                    *attack_speed,
+                    *attack_range as f32,
+                ));
+                // Attackers choose their targets among the attack candidates.
+                entity_commands.insert(AttackCandidates::default());
+            }
+            ComponentBlueprint::Targeting(targeting_rules) => {
+                entity_commands.insert(TargetingRules(targeting_rules.clone()));
+            }
+            ComponentBlueprint::Defense { armor_type, armor } => {
+                entity_commands.insert(Defense {
+                    armor_type: *armor_type,
+                    armor: *armor,
                 });
             }
             ComponentBlueprint::UnitSpawner {
-                unit_id,
+                units,
                 spawn_time,
+                units_per_cycle,
+            } => {
+                let queue: Vec<SpawnEntry> = units
+                    .iter()
+                    .filter_map(|spawn_option| {
+                        let Some(unit_blueprint) = faction.units.get(&spawn_option.unit_id) else {
+                            error!(
+                                "Unit {:?} doesn't exist in faction {:?}.",
+                                spawn_option.unit_id, faction.id
+                            );
+                            return None;
+                        };
+                        Some(SpawnEntry::new(unit_blueprint.clone(), spawn_option.weight))
+                    })
+                    .collect();
+                if queue.is_empty() {
+                    continue;
+                }
+                entity_commands.insert(UnitSpawner::new(*spawn_time, queue, *units_per_cycle));
+            }
+            ComponentBlueprint::Projectile {
+                speed,
+                sprite_handle,
+                size,
+                homing,
+                arc_height,
+                ..
+            } => {
+                entity_commands.insert(ProjectileLauncher {
+                    speed: *speed,
+                    sprite: sprite_handle.clone(),
+                    size: size.map(|(width, height)| Vec2::new(width, height)),
+                    homing: *homing,
+                    arc_height: *arc_height,
+                });
+            }
+            ComponentBlueprint::Splash {
+                radius,
+                falloff,
+                friendly_fire,
+            } => {
+                entity_commands.insert(SplashDamage {
+                    radius: *radius,
+                    falloff: *falloff,
+                    friendly_fire: *friendly_fire,
+                });
+            }
+            ComponentBlueprint::OnHit(status_effects) => {
+                entity_commands.insert(OnHitEffects(status_effects.clone()));
+            }
+            ComponentBlueprint::Supply(supply) => {
+                entity_commands.insert(SupplyProvider(*supply));
+            }
+            ComponentBlueprint::Auras(auras) => {
+                entity_commands.insert(Auras(auras.clone()));
+            }
+            ComponentBlueprint::Healer {
+                heal_amount,
+                cooldown,
+                heal_range,
             } => {
-                entity_commands.insert(UnitSpawner {
-                    spawn_time: *spawn_time,
-                    time_left: *spawn_time,
-                    unit_blueprint: player_settings.faction.units[unit_id].clone(),
+                entity_commands.insert(Healer {
+                    heal_amount: *heal_amount,
+                    cooldown: *cooldown,
+                    heal_range: *heal_range,
+                    time_till_next_heal: Timer::new(Duration::from_secs(0), TimerMode::Once),
                 });
             }
             ComponentBlueprint::VisionRange(range) => {
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use bevy_spatial::SpatialAccess;
+
+use crate::game::attack::BodyRadius;
+use crate::game::damage::{DamageEvent, DamageKind, DamageSource, DamageType};
+use crate::game::health::Health;
+use crate::game::teams::Team;
+use crate::game::vision::TeamEntityTree;
+
+/*
+Area-of-effect damage. Attacks with splash damage also hurt everything around the point of impact,
+which makes siege units effective against clumps of units and buildings.
+*/
+
+// --- Plugin ---
+
+pub struct SplashPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for SplashPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SplashEvent>().add_systems(
+            Update,
+            apply_splash_damage.run_if(in_state(self.state.clone())),
+        );
+    }
+}
+
+// --- Constants ---
+
+/// Extra distance searched in the spatial index, so large bodies whose edge is within
+/// the splash radius are found, even though their center is outside it.
+const SEARCH_MARGIN: f32 = 64.;
+
+// --- Components ---
+
+/// Makes attacks damage everything within a radius of the point of impact.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+pub struct SplashDamage {
+    pub radius: f32,
+    /// How much of the damage is lost at the edge of the radius, from 0 to 1.
+    /// The damage decreases linearly from the point of impact.
+    pub falloff: f32,
+    /// Whether the splash damages the attacker's own team too.
+    pub friendly_fire: bool,
+}
+
+// --- Events ---
+
+/// Sent when an attack with splash damage lands.
+#[derive(Event)]
+pub struct SplashEvent {
+    pub position: Vec2,
+    pub source: DamageSource,
+    pub damage: i32,
+    pub damage_type: DamageType,
+    pub splash: SplashDamage,
+    /// The target of the attack, which has already been damaged by the attack itself.
+    pub primary_target: Option<Entity>,
+}
+
+// --- Systems ---
+
+fn apply_splash_damage(
+    mut ev_splash: EventReader<SplashEvent>,
+    mut ev_damage: EventWriter<DamageEvent>,
+    team_entity_tree: Res<TeamEntityTree>,
+    query: Query<(&Transform, &Team, Option<&BodyRadius>), With<Health>>,
+) {
+    for event in ev_splash.read() {
+        let splash = event.splash;
+        if splash.radius <= 0. {
+            continue;
+        }
+
+        for (_, opt_entity) in
+            team_entity_tree.within_distance(event.position, splash.radius + SEARCH_MARGIN)
+        {
+            let Some(entity) = opt_entity else {
+                continue;
+            };
+            if Some(entity) == event.primary_target {
+                continue;
+            }
+            let Ok((transform, team, opt_body_radius)) = query.get(entity) else {
+                continue;
+            };
+            if *team == event.source.team && !splash.friendly_fire {
+                continue;
+            }
+
+            // Measured to the edge of the body, like attack ranges.
+            let body_radius = opt_body_radius.map_or(0., |body_radius| body_radius.0);
+            let distance =
+                (transform.translation.truncate().distance(event.position) - body_radius).max(0.);
+            if distance > splash.radius {
+                continue;
+            }
+
+            let falloff = splash.falloff.clamp(0., 1.) * distance / splash.radius;
+            ev_damage.send(DamageEvent {
+                source: Some(event.source),
+                target: entity,
+                amount: (event.damage as f32 * (1. - falloff)).round() as i32,
+                kind: DamageKind::Splash(event.damage_type),
+            });
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use std::mem::discriminant;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+
+use crate::game::damage::{DamageEvent, DamageKind, DamageSource};
+use crate::game::health::Health;
+
+/*
+Ongoing effects on units and buildings, like burning, being slowed or being stunned.
+Attackers apply them to the targets they hit with the on-hit effects from their faction data.
+*/
+
+// --- Plugin ---
+
+pub struct StatusEffectPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for StatusEffectPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StatusEffectEvent>().add_systems(
+            Update,
+            (apply_status_effects, tick_status_effects)
+                .chain()
+                .run_if(in_state(self.state.clone())),
+        );
+    }
+}
+
+// --- Constants ---
+
+/// Effects never tick more often than this, in seconds.
+const MIN_TICK_INTERVAL: f32 = 0.1;
+
+// --- Enums ---
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Reflect)]
+pub enum StatusEffectKind {
+    /// Deals damage every tick. Burn damage ignores armor.
+    Burn { damage_per_tick: i32 },
+    /// Scales movement speed. Only the strongest slow applies.
+    Slow { speed_multiplier: f32 },
+    /// Stops movement and pauses attacks.
+    Stun,
+}
+
+/// What happens, when an effect is applied to an entity already affected by the same kind of effect.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default, Reflect)]
+pub enum Stacking {
+    /// The duration of the existing effect is reset.
+    #[default]
+    Refresh,
+    /// Each application is a separate effect, up to a maximum number of stacks.
+    /// When the maximum is reached, the oldest stack is replaced.
+    Stack { max_stacks: usize },
+    /// The new effect is ignored, while the existing one lasts.
+    Ignore,
+}
+
+// --- Types ---
+
+/// Describes an effect to apply. Defined in the faction files.
+#[derive(Deserialize, Clone, PartialEq, Debug, Reflect)]
+pub struct StatusEffectBlueprint {
+    pub kind: StatusEffectKind,
+    /// How many seconds the effect lasts.
+    pub duration: f32,
+    /// Seconds between each tick of the effect. Only used by effects that do something every tick.
+    #[serde(default = "default_tick_interval")]
+    pub tick_interval: f32,
+    #[serde(default)]
+    pub stacking: Stacking,
+}
+
+#[derive(Clone, Debug, Reflect)]
+pub struct ActiveStatusEffect {
+    pub kind: StatusEffectKind,
+    pub stacking: Stacking,
+    /// The attacker that applied the effect.
+    pub source: DamageSource,
+    pub duration: Timer,
+    pub tick: Timer,
+}
+
+impl ActiveStatusEffect {
+    fn new(blueprint: &StatusEffectBlueprint, source: DamageSource) -> ActiveStatusEffect {
+        ActiveStatusEffect {
+            kind: blueprint.kind,
+            stacking: blueprint.stacking,
+            source,
+            duration: Timer::from_seconds(blueprint.duration, TimerMode::Once),
+            tick: Timer::from_seconds(
+                blueprint.tick_interval.max(MIN_TICK_INTERVAL),
+                TimerMode::Repeating,
+            ),
+        }
+    }
+}
+
+// --- Components ---
+
+/// Effects applied to the targets hit by the attacker.
+#[derive(Component, Clone, Reflect)]
+pub struct OnHitEffects(pub Vec<StatusEffectBlueprint>);
+
+/// The effects currently affecting an entity.
+#[derive(Component, Default, Reflect)]
+pub struct StatusEffects(pub Vec<ActiveStatusEffect>);
+
+impl StatusEffects {
+    pub fn is_stunned(&self) -> bool {
+        self.0
+            .iter()
+            .any(|effect| effect.kind == StatusEffectKind::Stun)
+    }
+
+    /// How much movement speed is scaled by the effects.
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.is_stunned() {
+            return 0.;
+        }
+        self.0
+            .iter()
+            .filter_map(|effect| match effect.kind {
+                StatusEffectKind::Slow { speed_multiplier } => Some(speed_multiplier.max(0.)),
+                _ => None,
+            })
+            .fold(1., f32::min)
+    }
+
+    fn add(&mut self, new_effect: ActiveStatusEffect) {
+        let same_kind = |effect: &ActiveStatusEffect| {
+            discriminant(&effect.kind) == discriminant(&new_effect.kind)
+        };
+
+        match new_effect.stacking {
+            Stacking::Refresh => {
+                if let Some(effect) = self.0.iter_mut().find(|effect| same_kind(effect)) {
+                    *effect = new_effect;
+                    return;
+                }
+            }
+            Stacking::Stack { max_stacks } => {
+                let stacks = self.0.iter().filter(|effect| same_kind(effect)).count();
+                if stacks >= max_stacks.max(1) {
+                    if let Some(index) = self.0.iter().position(same_kind) {
+                        self.0.remove(index);
+                    }
+                }
+            }
+            Stacking::Ignore => {
+                if self.0.iter().any(same_kind) {
+                    return;
+                }
+            }
+        }
+        self.0.push(new_effect);
+    }
+}
+
+// --- Events ---
+
+/// Sent when an attack hits a target, to apply the attacker's on-hit effects.
+#[derive(Event)]
+pub struct StatusEffectEvent {
+    pub target: Entity,
+    pub source: DamageSource,
+    pub effects: Vec<StatusEffectBlueprint>,
+}
+
+// --- Systems ---
+
+fn apply_status_effects(
+    mut commands: Commands,
+    mut ev_status_effect: EventReader<StatusEffectEvent>,
+    mut query: Query<Option<&mut StatusEffects>, With<Health>>,
+) {
+    // Entities without status effects get the component once, with all the new effects.
+    let mut new_status_effects: HashMap<Entity, StatusEffects> = HashMap::new();
+
+    for event in ev_status_effect.read() {
+        let Ok(opt_status_effects) = query.get_mut(event.target) else {
+            continue;
+        };
+        let new_effects = event
+            .effects
+            .iter()
+            .map(|blueprint| ActiveStatusEffect::new(blueprint, event.source));
+
+        match opt_status_effects {
+            Some(mut status_effects) => new_effects.for_each(|effect| status_effects.add(effect)),
+            None => {
+                let status_effects = new_status_effects.entry(event.target).or_default();
+                new_effects.for_each(|effect| status_effects.add(effect));
+            }
+        }
+    }
+
+    for (entity, status_effects) in new_status_effects {
+        commands.entity(entity).insert(status_effects);
+    }
+}
+
+fn tick_status_effects(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut StatusEffects)>,
+    mut ev_damage: EventWriter<DamageEvent>,
+    time: Res<Time>,
+) {
+    for (entity, mut status_effects) in query.iter_mut() {
+        for effect in status_effects.0.iter_mut() {
+            effect.duration.tick(time.delta());
+            effect.tick.tick(time.delta());
+
+            if let StatusEffectKind::Burn { damage_per_tick } = effect.kind {
+                let ticks = effect.tick.times_finished_this_tick() as i32;
+                if ticks > 0 {
+                    ev_damage.send(DamageEvent {
+                        source: Some(effect.source),
+                        target: entity,
+                        amount: damage_per_tick * ticks,
+                        kind: DamageKind::Burn,
+                    });
+                }
+            }
+        }
+
+        status_effects
+            .0
+            .retain(|effect| !effect.duration.finished());
+        if status_effects.0.is_empty() {
+            commands.entity(entity).remove::<StatusEffects>();
+        }
+    }
+}
+
+// --- Helper functions ---
+
+fn default_tick_interval() -> f32 {
+    1.
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::game::construction::UnderConstruction;
+use crate::game::teams::Team;
+
+/*
+Limits how many units each team can have at once. Every unit uses some supply,
+and spawners wait while their team doesn't have room for another unit.
+Supply buildings raise the cap of their team, once they are constructed.
+*/
+
+// --- Plugin ---
+
+pub struct SupplyPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for SupplyPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Supplies>()
+            .add_systems(Update, update_supplies.run_if(in_state(self.state.clone())));
+    }
+}
+
+// --- Constants ---
+
+/// The supply cap of a team without any supply buildings.
+const BASE_SUPPLY_CAP: u32 = 20;
+/// Supply buildings can't raise the cap above this.
+const MAX_SUPPLY_CAP: u32 = 100;
+
+// --- Resources ---
+
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct Supply {
+    pub used: u32,
+    pub cap: u32,
+}
+
+impl Default for Supply {
+    fn default() -> Self {
+        Supply {
+            used: 0,
+            cap: BASE_SUPPLY_CAP,
+        }
+    }
+}
+
+impl Supply {
+    pub fn is_capped(&self) -> bool {
+        self.used >= self.cap
+    }
+}
+
+/// The supply of every team, counted every frame.
+#[derive(Resource, Default, Reflect)]
+pub struct Supplies(pub HashMap<Team, Supply>);
+
+impl Supplies {
+    pub fn get(&self, team: Team) -> Supply {
+        self.0.get(&team).copied().unwrap_or_default()
+    }
+
+    /// Uses the supply for a new unit. Returns false and uses nothing, if the team is at its cap.
+    pub fn try_use(&mut self, team: Team, supply: u32) -> bool {
+        let team_supply = self.0.entry(team).or_default();
+        if team_supply.used + supply > team_supply.cap {
+            return false;
+        }
+        team_supply.used += supply;
+        true
+    }
+}
+
+// --- Components ---
+
+/// The supply a unit uses.
+#[derive(Component, Reflect)]
+pub struct SupplyCost(pub u32);
+
+/// Raises the supply cap of the team.
+#[derive(Component, Reflect)]
+pub struct SupplyProvider(pub u32);
+
+// --- Systems ---
+
+pub fn update_supplies(
+    mut supplies: ResMut<Supplies>,
+    cost_query: Query<(&Team, &SupplyCost)>,
+    provider_query: Query<(&Team, &SupplyProvider), Without<UnderConstruction>>,
+) {
+    let mut new_supplies: HashMap<Team, Supply> = [Team::Red, Team::Blue]
+        .into_iter()
+        .map(|team| (team, Supply::default()))
+        .collect();
+
+    for (team, supply_cost) in cost_query.iter() {
+        new_supplies.entry(*team).or_default().used += supply_cost.0;
+    }
+    for (team, supply_provider) in provider_query.iter() {
+        new_supplies.entry(*team).or_default().cap += supply_provider.0;
+    }
+    for supply in new_supplies.values_mut() {
+        supply.cap = supply.cap.min(MAX_SUPPLY_CAP);
+    }
+
+    supplies.0 = new_supplies;
+}
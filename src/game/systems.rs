@@ -26,3 +26,8 @@ pub fn pause_simulation(mut time: ResMut<Time<Virtual>>) {
 pub fn unpause_simulation(mut time: ResMut<Time<Virtual>>) {
     time.unpause()
 }
+
+/// Makes sure the next game starts with a running simulation.
+pub fn reset_simulation(mut next_state: ResMut<NextState<SimulationState>>) {
+    next_state.set(SimulationState::Running);
+}
@@ -0,0 +1,204 @@
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::attack::{AttackStats, AttackTarget, BodyRadius};
+use crate::game::buildings::Building;
+use crate::game::health::Health;
+use crate::game::movement::Stationary;
+use crate::game::units::Unit;
+use crate::game::vision::InVision;
+
+/*
+Decides what attackers should attack. Attackers keep a list of enemies in vision,
+so a new target can be picked right away, when the current one dies.
+Stationary attackers, like towers, can't move closer, so they only pick targets within range.
+*/
+
+// --- Plugin ---
+
+pub struct TargetingPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for TargetingPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (sync_attack_candidates, update_attack_targets)
+                .chain()
+                .run_if(in_state(self.state.clone())),
+        );
+    }
+}
+
+// --- Enums ---
+
+/// Rules for choosing between attack targets.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+pub enum TargetRule {
+    Closest,
+    LowestHealth,
+    PreferUnits,
+    PreferBuildings,
+}
+
+// --- Components ---
+
+/// The enemies an attacker can choose to attack. Mirrors the enemies in vision,
+/// but dead enemies are removed right away.
+#[derive(Component, Default, Reflect)]
+pub struct AttackCandidates(pub Vec<Entity>);
+
+/// The rules used to choose an attack target, in order of importance.
+/// If the first rule considers two targets equal, the next rule decides and so on.
+/// Attackers without it attack the closest enemy.
+#[derive(Component, Reflect)]
+pub struct TargetingRules(pub Vec<TargetRule>);
+
+// --- Systems ---
+
+fn sync_attack_candidates(mut query: Query<(&InVision, &mut AttackCandidates), Changed<InVision>>) {
+    for (in_vision, mut attack_candidates) in query.iter_mut() {
+        attack_candidates.0.clone_from(&in_vision.enemies);
+    }
+}
+
+/// Picks a target for attackers without one and replaces targets that have died.
+/// Stationary attackers also replace targets that have left their range.
+#[allow(clippy::type_complexity)]
+fn update_attack_targets(
+    mut commands: Commands,
+    mut attacker_query: Query<(
+        Entity,
+        &Transform,
+        &AttackStats,
+        Option<&BodyRadius>,
+        &mut AttackCandidates,
+        Option<&TargetingRules>,
+        Option<&AttackTarget>,
+        Has<Stationary>,
+    )>,
+    candidate_query: Query<(
+        &Transform,
+        &Health,
+        Has<Unit>,
+        Has<Building>,
+        Option<&BodyRadius>,
+    )>,
+) {
+    for (
+        entity,
+        transform,
+        attack_stats,
+        opt_body_radius,
+        mut attack_candidates,
+        opt_targeting_rules,
+        opt_attack_target,
+        is_stationary,
+    ) in attacker_query.iter_mut()
+    {
+        let position = transform.translation.truncate();
+
+        let is_alive = |candidate: Entity| {
+            candidate_query
+                .get(candidate)
+                .is_ok_and(|(_, health, _, _, _)| health.health > 0)
+        };
+        let can_attack = |candidate: Entity| {
+            candidate_query.get(candidate).is_ok_and(
+                |(candidate_transform, health, _, _, opt_candidate_body_radius)| {
+                    health.health > 0
+                        && (!is_stationary
+                            || attack_stats.in_range(
+                                (position, opt_body_radius),
+                                (
+                                    candidate_transform.translation.truncate(),
+                                    opt_candidate_body_radius,
+                                ),
+                            ))
+                },
+            )
+        };
+
+        attack_candidates.0.retain(|candidate| is_alive(*candidate));
+
+        // Keep attacking the current target, as long as it can be attacked.
+        if let Some(attack_target) = opt_attack_target {
+            if can_attack(attack_target.0) {
+                continue;
+            }
+        }
+
+        let targeting_rules =
+            opt_targeting_rules.map_or(&[TargetRule::Closest][..], |rules| rules.0.as_slice());
+
+        let new_target = attack_candidates
+            .0
+            .iter()
+            .copied()
+            .filter(|candidate| can_attack(*candidate))
+            .min_by(|a, b| {
+                let (
+                    Ok((a_transform, a_health, a_is_unit, a_is_building, _)),
+                    Ok((b_transform, b_health, b_is_unit, b_is_building, _)),
+                ) = (candidate_query.get(*a), candidate_query.get(*b))
+                else {
+                    return Ordering::Equal;
+                };
+                compare_targets(
+                    position,
+                    (a_transform, a_health, a_is_unit, a_is_building),
+                    (b_transform, b_health, b_is_unit, b_is_building),
+                    targeting_rules,
+                )
+            });
+
+        match new_target {
+            Some(target) => {
+                commands.entity(entity).insert(AttackTarget(target));
+            }
+            None if opt_attack_target.is_some() => {
+                commands.entity(entity).remove::<AttackTarget>();
+            }
+            None => {}
+        }
+    }
+}
+
+// --- Helper functions ---
+
+/// Orders two potential targets, so the preferred target comes first.
+fn compare_targets(
+    position: Vec2,
+    (a_transform, a_health, a_is_unit, a_is_building): (&Transform, &Health, bool, bool),
+    (b_transform, b_health, b_is_unit, b_is_building): (&Transform, &Health, bool, bool),
+    targeting_rules: &[TargetRule],
+) -> Ordering {
+    for rule in targeting_rules {
+        let ordering = match rule {
+            TargetRule::Closest => {
+                let a_distance = a_transform
+                    .translation
+                    .truncate()
+                    .distance_squared(position);
+                let b_distance = b_transform
+                    .translation
+                    .truncate()
+                    .distance_squared(position);
+                a_distance
+                    .partial_cmp(&b_distance)
+                    .unwrap_or(Ordering::Equal)
+            }
+            TargetRule::LowestHealth => a_health.health.cmp(&b_health.health),
+            // Reversed, as true should come before false.
+            TargetRule::PreferUnits => b_is_unit.cmp(&a_is_unit),
+            TargetRule::PreferBuildings => b_is_building.cmp(&a_is_building),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
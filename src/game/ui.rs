@@ -2,10 +2,27 @@
 
 use bevy::prelude::*;
 
-use crate::game::building_spawning::InitPlaceBuildingEvent;
+use crate::game::building_removal::{sell_value, Removal, RemoveBuildingEvent};
+use crate::game::building_spawning::{
+    building_name, BuildingPlacementRefusedEvent, InitPlaceBuildingEvent, PlacementRefusal,
+};
+use crate::game::building_upgrades::{
+    BuildingUpgradeRefusedEvent, UpgradeBuildingEvent, UpgradingBuilding,
+};
+use crate::game::buildings::{count_owned_buildings, Building};
+use crate::game::construction::UnderConstruction;
+use crate::game::economy::Treasuries;
+use crate::game::health::Health;
+use crate::game::research::{Research, ResearchQueue, ResearchRefusedEvent, ResearchRequestEvent};
+use crate::game::selection::SelectedBuilding;
+use crate::game::spawning::BlueprintId;
+use crate::game::supply::Supplies;
+use crate::game::teams::Team;
+use crate::game::unit_spawning::{RallyPoint, SetRallyPointEvent, UnitSpawner};
+use crate::game::waypoints::WaypointMap;
 use crate::game::InGameTag;
 use crate::load_game::load_factions::BuildingBlueprint;
-use crate::resources::PlayerSettings;
+use crate::resources::{PlayerSettings, TeamSettings};
 
 pub struct UiPlugin<S: States> {
     pub state: S,
@@ -17,14 +34,29 @@ impl<S: States> Plugin for UiPlugin<S> {
             .add_systems(
                 Update,
                 (
-                    building_btn_interaction_handler,
+                    update_building_btn_availability,
+                    building_btn_interaction_handler.after(update_building_btn_availability),
                     building_btn_action_handler,
+                    update_gold_text,
+                    update_supply_text,
+                    show_refused_feedback,
+                    hide_feedback_text.after(show_refused_feedback),
+                    update_building_info_panel,
+                    update_progress_text.after(update_building_info_panel),
+                    update_sell_btn_text.after(update_building_info_panel),
+                    panel_btn_interaction_handler,
+                    panel_btn_action_handler,
                 )
                     .run_if(in_state(self.state.clone())),
             );
     }
 }
 
+// --- Constants ---
+
+/// Seconds feedback messages are shown to the player.
+const FEEDBACK_DURATION: f32 = 3.;
+
 // --- Components ---
 
 enum ButtonAction {
@@ -35,14 +67,62 @@ enum ButtonAction {
 #[derive(Component)]
 struct BtnBuilding {
     action: ButtonAction,
+    /// If the team owns the buildings required by the action.
+    available: bool,
 }
 
 #[derive(Component)]
 struct BtnBuildingImage;
 
+#[derive(Component)]
+struct GoldText;
+
+#[derive(Component)]
+struct SupplyText;
+
+/// Shows the selected building and the upgrades it can research.
+#[derive(Component)]
+struct BuildingInfoPanel;
+
+/// Shows the progress of the research or upgrade of the selected building.
+#[derive(Component)]
+struct ProgressText;
+
+/// Actions on the selected building.
+enum PanelAction {
+    /// Queue the upgrade with the given ID.
+    Research(String),
+    /// Upgrade the building into the building with the given ID.
+    UpgradeBuilding(String),
+    /// Send the spawned units down the next lane.
+    CycleLane,
+    /// Sell or demolish the building.
+    Remove(Removal),
+}
+
+#[derive(Component)]
+struct BtnPanel {
+    action: PanelAction,
+}
+
+/// Shows short messages to the player, for instance why a building couldn't be placed.
+#[derive(Component)]
+struct FeedbackText {
+    time_left: Timer,
+}
+
 // --- Systems ---
 
-fn setup_ui(mut commands: Commands, player_settings: Res<PlayerSettings>) {
+fn setup_ui(
+    mut commands: Commands,
+    player_settings: Res<PlayerSettings>,
+    team_settings: Res<TeamSettings>,
+) {
+    let Some(faction) = team_settings.faction(player_settings.team) else {
+        error!("The player's team has no faction to build from.");
+        return;
+    };
+
     // Layout.
     commands
         .spawn((
@@ -61,9 +141,56 @@ fn setup_ui(mut commands: Commands, player_settings: Res<PlayerSettings>) {
         ))
         .with_children(|layout| {
             // Top UI bar.
-            layout.spawn(NodeBundle {
-                ..Default::default()
-            });
+            layout
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(24.),
+                        padding: UiRect::all(Val::Px(12.)),
+                        ..Default::default()
+                    },
+                    background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.5)),
+                    ..Default::default()
+                })
+                .with_children(|top_bar| {
+                    top_bar.spawn((
+                        GoldText,
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 24.,
+                                color: Color::GOLD,
+                                ..Default::default()
+                            },
+                        ),
+                    ));
+                    top_bar.spawn((
+                        SupplyText,
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 24.,
+                                color: Color::WHITE,
+                                ..Default::default()
+                            },
+                        ),
+                    ));
+                    top_bar.spawn((
+                        FeedbackText {
+                            time_left: Timer::from_seconds(FEEDBACK_DURATION, TimerMode::Once),
+                        },
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 24.,
+                                color: Color::ORANGE_RED,
+                                ..Default::default()
+                            },
+                        ),
+                    ));
+                });
             // Bottom UI bar.
             layout
                 .spawn(NodeBundle {
@@ -79,49 +206,93 @@ fn setup_ui(mut commands: Commands, player_settings: Res<PlayerSettings>) {
                 .with_children(|bottom_bar| {
                     // Building menu.
                     bottom_bar
-                        .spawn(NodeBundle {
-                            style: Style {
-                                display: Display::Grid,
-                                // Add padding around the grid.
-                                padding: UiRect::all(Val::Px(12.)),
-                                // Set a height and the aspect ratio to be 1:1, so it will auto set the width
-                                // to be equal to the height.
-                                height: Val::Percent(30.),
-                                aspect_ratio: Some(1.25),
-                                // Set the grid to have 4 columns.
-                                grid_template_columns: RepeatedGridTrack::flex(4, 1.0),
-                                // Set the grid to have 4 rows.
-                                grid_template_rows: RepeatedGridTrack::flex(3, 1.0),
-                                // Set a gap between each cell.
-                                row_gap: Val::Px(12.),
-                                column_gap: Val::Px(12.),
+                        .spawn((
+                            // Lets the selection know the cursor is over the UI.
+                            Interaction::default(),
+                            NodeBundle {
+                                style: Style {
+                                    display: Display::Grid,
+                                    // Add padding around the grid.
+                                    padding: UiRect::all(Val::Px(12.)),
+                                    // Set a height and the aspect ratio to be 1:1, so it will auto set the width
+                                    // to be equal to the height.
+                                    height: Val::Percent(30.),
+                                    aspect_ratio: Some(1.25),
+                                    // Set the grid to have 4 columns.
+                                    grid_template_columns: RepeatedGridTrack::flex(4, 1.0),
+                                    // Set the grid to have 4 rows.
+                                    grid_template_rows: RepeatedGridTrack::flex(3, 1.0),
+                                    // Set a gap between each cell.
+                                    row_gap: Val::Px(12.),
+                                    column_gap: Val::Px(12.),
+                                    ..Default::default()
+                                },
+                                background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.5)),
                                 ..Default::default()
                             },
-                            background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.5)),
-                            ..Default::default()
-                        })
+                        ))
                         .with_children(|building_menu| {
-                            for building in player_settings.faction.buildings.values() {
+                            // Lower tiers first. Buildings only reached by upgrading aren't shown.
+                            let mut buildings: Vec<_> = faction
+                                .buildings
+                                .values()
+                                .filter(|building| building.buildable)
+                                .collect();
+                            buildings.sort_by_key(|building| {
+                                (building.tier, building.cost, building.id.clone())
+                            });
+                            for building in buildings {
                                 spawn_building_btn(building_menu, building);
                             }
                         });
+                    // Info panel of the selected building. Filled in, when a building is selected.
+                    bottom_bar.spawn((
+                        BuildingInfoPanel,
+                        Interaction::default(),
+                        NodeBundle {
+                            style: Style {
+                                display: Display::None,
+                                flex_direction: FlexDirection::Column,
+                                row_gap: Val::Px(6.),
+                                min_width: Val::Px(240.),
+                                padding: UiRect::all(Val::Px(12.)),
+                                ..Default::default()
+                            },
+                            background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.5)),
+                            ..Default::default()
+                        },
+                    ));
                 });
         });
 }
 
+#[allow(clippy::type_complexity)]
 fn building_btn_action_handler(
     mut ev_init_place_building: EventWriter<InitPlaceBuildingEvent>,
+    mut ev_placement_refused: EventWriter<BuildingPlacementRefusedEvent>,
     query: Query<(&Interaction, &BtnBuilding), Changed<Interaction>>,
+    building_query: Query<(&Team, &BlueprintId), (With<Building>, Without<UnderConstruction>)>,
     player_settings: Res<PlayerSettings>,
+    team_settings: Res<TeamSettings>,
 ) {
     for (interaction, building_btn) in query.iter() {
         match *interaction {
             Interaction::Pressed => match &building_btn.action {
                 ButtonAction::BuildBuilding(building_blueprint) => {
-                    ev_init_place_building.send(InitPlaceBuildingEvent(
-                        building_blueprint.clone(),
-                        player_settings.team,
-                    ));
+                    let team = player_settings.team;
+                    let owned_buildings = count_owned_buildings(team, building_query.iter());
+                    if let Some(required) = building_blueprint.missing_requirement(&owned_buildings)
+                    {
+                        ev_placement_refused.send(BuildingPlacementRefusedEvent {
+                            team,
+                            reason: PlacementRefusal::MissingRequirement {
+                                building: building_name(&team_settings, team, required),
+                            },
+                        });
+                        continue;
+                    }
+                    ev_init_place_building
+                        .send(InitPlaceBuildingEvent(building_blueprint.clone(), team));
                 }
             },
             Interaction::Hovered | Interaction::None => {}
@@ -131,30 +302,494 @@ fn building_btn_action_handler(
 
 #[allow(clippy::type_complexity)]
 fn building_btn_interaction_handler(
-    query: Query<(&Interaction, &Children), (Changed<Interaction>, With<BtnBuilding>)>,
+    query: Query<
+        (&Interaction, &BtnBuilding, &Children),
+        Or<(Changed<Interaction>, Changed<BtnBuilding>)>,
+    >,
     mut image_query: Query<&mut BackgroundColor>,
 ) {
-    for (interaction, children) in &query {
+    for (interaction, building_btn, children) in &query {
         let mut image_bg_color = image_query.get_mut(children[0]).unwrap();
-        match *interaction {
-            Interaction::Hovered => {
-                *image_bg_color = Color::rgba(1., 1., 1., 0.5).into();
+        *image_bg_color = match (*interaction, building_btn.available) {
+            (_, false) => Color::rgba(0.3, 0.3, 0.3, 1.).into(),
+            (Interaction::Hovered | Interaction::Pressed, true) => {
+                Color::rgba(1., 1., 1., 0.5).into()
+            }
+            (Interaction::None, true) => Color::WHITE.into(),
+        };
+    }
+}
+
+/// Greys out the buildings the player doesn't own the required buildings for.
+#[allow(clippy::type_complexity)]
+fn update_building_btn_availability(
+    mut btn_query: Query<&mut BtnBuilding>,
+    building_query: Query<(&Team, &BlueprintId), (With<Building>, Without<UnderConstruction>)>,
+    player_settings: Res<PlayerSettings>,
+) {
+    let owned_buildings = count_owned_buildings(player_settings.team, building_query.iter());
+    for mut building_btn in btn_query.iter_mut() {
+        let available = match &building_btn.action {
+            ButtonAction::BuildBuilding(building_blueprint) => building_blueprint
+                .missing_requirement(&owned_buildings)
+                .is_none(),
+        };
+        if building_btn.available != available {
+            building_btn.available = available;
+        }
+    }
+}
+
+fn update_gold_text(
+    treasuries: Res<Treasuries>,
+    player_settings: Res<PlayerSettings>,
+    mut query: Query<&mut Text, With<GoldText>>,
+) {
+    let Some(treasury) = treasuries.0.get(&player_settings.team) else {
+        return;
+    };
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Gold: {} (+{})", treasury.gold, treasury.income);
+    }
+}
+
+/// Shows the supply in red, while the player's spawners are waiting for room.
+fn update_supply_text(
+    supplies: Res<Supplies>,
+    player_settings: Res<PlayerSettings>,
+    mut query: Query<&mut Text, With<SupplyText>>,
+) {
+    let supply = supplies.get(player_settings.team);
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Supply: {}/{}", supply.used, supply.cap);
+        text.sections[0].style.color = if supply.is_capped() {
+            Color::ORANGE_RED
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+fn show_refused_feedback(
+    mut ev_placement_refused: EventReader<BuildingPlacementRefusedEvent>,
+    mut ev_research_refused: EventReader<ResearchRefusedEvent>,
+    mut ev_upgrade_refused: EventReader<BuildingUpgradeRefusedEvent>,
+    player_settings: Res<PlayerSettings>,
+    mut query: Query<(&mut Text, &mut FeedbackText)>,
+) {
+    // Only the player should be told about their own refused actions.
+    let reasons = ev_placement_refused
+        .read()
+        .filter(|ev| ev.team == player_settings.team)
+        .map(|ev| ev.reason.to_string())
+        .chain(
+            ev_research_refused
+                .read()
+                .filter(|ev| ev.team == player_settings.team)
+                .map(|ev| ev.reason.to_string()),
+        )
+        .chain(
+            ev_upgrade_refused
+                .read()
+                .filter(|ev| ev.team == player_settings.team)
+                .map(|ev| ev.reason.to_string()),
+        );
+
+    for reason in reasons {
+        for (mut text, mut feedback_text) in query.iter_mut() {
+            text.sections[0].value.clone_from(&reason);
+            feedback_text.time_left.reset();
+        }
+    }
+}
+
+fn hide_feedback_text(mut query: Query<(&mut Text, &mut FeedbackText)>, time: Res<Time<Real>>) {
+    for (mut text, mut feedback_text) in query.iter_mut() {
+        if feedback_text.time_left.tick(time.delta()).just_finished() {
+            text.sections[0].value.clear();
+        }
+    }
+}
+
+/// Rebuilds the info panel, when the selected building, its research queue or the researched upgrades change,
+/// or when the selected building finishes construction.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn update_building_info_panel(
+    mut commands: Commands,
+    selected_building: Res<SelectedBuilding>,
+    research: Res<Research>,
+    building_query: Query<(
+        Ref<BlueprintId>,
+        Option<Ref<ResearchQueue>>,
+        Option<Ref<UpgradingBuilding>>,
+        Option<Ref<RallyPoint>>,
+        Has<UnitSpawner>,
+        Has<UnderConstruction>,
+    )>,
+    mut constructed: RemovedComponents<UnderConstruction>,
+    waypoint_map: Res<WaypointMap>,
+    mut panel_query: Query<(Entity, &mut Style), With<BuildingInfoPanel>>,
+    player_settings: Res<PlayerSettings>,
+    team_settings: Res<TeamSettings>,
+) {
+    let selected = selected_building
+        .0
+        .and_then(|entity| building_query.get(entity).ok());
+    let building_changed = selected.as_ref().is_some_and(
+        |(blueprint_id, opt_research_queue, opt_upgrading_building, opt_rally_point, _, _)| {
+            blueprint_id.is_changed()
+                || opt_research_queue
+                    .as_ref()
+                    .is_some_and(|research_queue| research_queue.is_changed())
+                || opt_upgrading_building
+                    .as_ref()
+                    .is_some_and(|upgrading_building| upgrading_building.is_changed())
+                || opt_rally_point
+                    .as_ref()
+                    .is_some_and(|rally_point| rally_point.is_changed())
+        },
+    );
+    let selected_constructed = constructed
+        .read()
+        .any(|entity| selected_building.0 == Some(entity));
+    if !selected_building.is_changed()
+        && !research.is_changed()
+        && !building_changed
+        && !selected_constructed
+    {
+        return;
+    }
+
+    let Ok((panel, mut style)) = panel_query.get_single_mut() else {
+        return;
+    };
+    commands.entity(panel).despawn_descendants();
+
+    let team = player_settings.team;
+    let (
+        Some((
+            blueprint_id,
+            opt_research_queue,
+            opt_upgrading_building,
+            opt_rally_point,
+            is_spawner,
+            is_under_construction,
+        )),
+        Some(faction),
+    ) = (selected, team_settings.faction(team))
+    else {
+        style.display = Display::None;
+        return;
+    };
+    let Some(building) = faction.buildings.get(&blueprint_id.0) else {
+        style.display = Display::None;
+        return;
+    };
+    style.display = Display::Flex;
+
+    let mut upgrades: Vec<_> = faction
+        .upgrades
+        .values()
+        .filter(|upgrade| upgrade.researched_at == building.id)
+        .collect();
+    upgrades.sort_by_key(|upgrade| upgrade.cost);
+
+    commands.entity(panel).with_children(|info_panel| {
+        info_panel.spawn(TextBundle::from_section(
+            building.name.clone(),
+            TextStyle {
+                font_size: 20.,
+                color: Color::WHITE,
+                ..Default::default()
+            },
+        ));
+        if is_under_construction || opt_research_queue.is_some() || !building.upgrades_to.is_empty()
+        {
+            info_panel.spawn((
+                ProgressText,
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.,
+                        color: Color::WHITE,
+                        ..Default::default()
+                    },
+                ),
+            ));
+        }
+
+        if is_spawner {
+            let lanes = waypoint_map.get_lanes(team);
+            let lane = opt_rally_point
+                .and_then(|rally_point| rally_point.0)
+                .and_then(|start_waypoint| lanes.iter().position(|lane| *lane == start_waypoint));
+            let label = match lane {
+                Some(index) => format!("Lane: {} of {}", index + 1, lanes.len()),
+                None => "Lane: Closest".to_string(),
+            };
+            spawn_panel_btn(info_panel, PanelAction::CycleLane, label, lanes.len() > 1);
+        }
+
+        for building_upgrade in building.upgrades_to.iter() {
+            let name = building_name(&team_settings, team, &building_upgrade.building_id);
+            let label = format!("Upgrade to {} - {} gold", name, building_upgrade.cost);
+            spawn_panel_btn(
+                info_panel,
+                PanelAction::UpgradeBuilding(building_upgrade.building_id.clone()),
+                label,
+                opt_upgrading_building.is_none() && !is_under_construction,
+            );
+        }
+
+        // The label is filled in with the current sell value, or the refund for cancelling the construction.
+        spawn_panel_btn(
+            info_panel,
+            PanelAction::Remove(Removal::Sell),
+            String::new(),
+            true,
+        );
+        spawn_panel_btn(
+            info_panel,
+            PanelAction::Remove(Removal::Demolish),
+            "Demolish".to_string(),
+            true,
+        );
+
+        let Some(research_queue) = opt_research_queue else {
+            return;
+        };
+        for upgrade in upgrades {
+            let queued = research_queue
+                .queue
+                .iter()
+                .any(|queued| queued.id == upgrade.id);
+            let (label, available) = if research.is_researched(team, &upgrade.id) {
+                (format!("{} (researched)", upgrade.name), false)
+            } else if queued {
+                (format!("{} (queued)", upgrade.name), false)
+            } else {
+                let has_prerequisites = upgrade
+                    .requires
+                    .iter()
+                    .all(|required| research.is_researched(team, required));
+                (
+                    format!("{} - {} gold", upgrade.name, upgrade.cost),
+                    has_prerequisites,
+                )
+            };
+            spawn_panel_btn(
+                info_panel,
+                PanelAction::Research(upgrade.id.clone()),
+                label,
+                available,
+            );
+        }
+    });
+}
+
+fn update_progress_text(
+    selected_building: Res<SelectedBuilding>,
+    building_query: Query<(
+        Option<&UnderConstruction>,
+        Option<&ResearchQueue>,
+        Option<&UpgradingBuilding>,
+    )>,
+    mut text_query: Query<&mut Text, With<ProgressText>>,
+) {
+    let Some((opt_under_construction, opt_research_queue, opt_upgrading_building)) =
+        selected_building
+            .0
+            .and_then(|entity| building_query.get(entity).ok())
+    else {
+        return;
+    };
+
+    let progress = if let Some(under_construction) = opt_under_construction {
+        format!(
+            "Constructing: {:.0}%",
+            under_construction.progress.fraction() * 100.
+        )
+    } else if let Some(upgrading_building) = opt_upgrading_building {
+        format!(
+            "Upgrading to {}: {:.0}%",
+            upgrading_building.building_blueprint.name,
+            upgrading_building.progress.fraction() * 100.
+        )
+    } else if let Some(research_queue) =
+        opt_research_queue.filter(|research_queue| !research_queue.queue.is_empty())
+    {
+        format!(
+            "Researching {}: {:.0}%",
+            research_queue.queue[0].name,
+            research_queue.progress.fraction() * 100.
+        )
+    } else {
+        "Idle.".to_string()
+    };
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value.clone_from(&progress);
+    }
+}
+
+/// Keeps the sell button up to date with the gold selling the selected building refunds.
+/// Buildings under construction are cancelled instead of sold.
+fn update_sell_btn_text(
+    selected_building: Res<SelectedBuilding>,
+    building_query: Query<(&BlueprintId, Option<&Health>, Has<UnderConstruction>)>,
+    btn_query: Query<(&BtnPanel, &Children)>,
+    mut text_query: Query<&mut Text>,
+    player_settings: Res<PlayerSettings>,
+    team_settings: Res<TeamSettings>,
+) {
+    let Some((blueprint_id, opt_health, is_under_construction)) = selected_building
+        .0
+        .and_then(|entity| building_query.get(entity).ok())
+    else {
+        return;
+    };
+    let Some(building_blueprint) = team_settings
+        .faction(player_settings.team)
+        .and_then(|faction| faction.buildings.get(&blueprint_id.0))
+    else {
+        return;
+    };
+    let refund = sell_value(building_blueprint, opt_health, is_under_construction);
+    let label = if is_under_construction {
+        format!("Cancel construction (+{} gold)", refund)
+    } else {
+        format!("Sell (+{} gold)", refund)
+    };
+
+    for (panel_btn, children) in btn_query.iter() {
+        if !matches!(panel_btn.action, PanelAction::Remove(Removal::Sell)) {
+            continue;
+        }
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                if text.sections[0].value != label {
+                    text.sections[0].value.clone_from(&label);
+                }
             }
-            Interaction::None => {
-                *image_bg_color = Color::WHITE.into();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn panel_btn_action_handler(
+    mut ev_research_request: EventWriter<ResearchRequestEvent>,
+    mut ev_upgrade_building: EventWriter<UpgradeBuildingEvent>,
+    mut ev_set_rally_point: EventWriter<SetRallyPointEvent>,
+    mut ev_remove_building: EventWriter<RemoveBuildingEvent>,
+    query: Query<(&Interaction, &BtnPanel), Changed<Interaction>>,
+    rally_point_query: Query<&RallyPoint>,
+    selected_building: Res<SelectedBuilding>,
+    waypoint_map: Res<WaypointMap>,
+    player_settings: Res<PlayerSettings>,
+) {
+    let Some(building) = selected_building.0 else {
+        return;
+    };
+
+    for (interaction, panel_btn) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match &panel_btn.action {
+            PanelAction::Research(upgrade_id) => {
+                ev_research_request.send(ResearchRequestEvent {
+                    building,
+                    upgrade_id: upgrade_id.clone(),
+                });
+            }
+            PanelAction::UpgradeBuilding(building_id) => {
+                ev_upgrade_building.send(UpgradeBuildingEvent {
+                    building,
+                    building_id: building_id.clone(),
+                });
+            }
+            PanelAction::CycleLane => {
+                // Goes through the lanes from top to bottom, and then back to the closest lane.
+                let lanes = waypoint_map.get_lanes(player_settings.team);
+                let current_lane = rally_point_query
+                    .get(building)
+                    .ok()
+                    .and_then(|rally_point| rally_point.0)
+                    .and_then(|start_waypoint| {
+                        lanes.iter().position(|lane| *lane == start_waypoint)
+                    });
+                let next_lane = match current_lane {
+                    Some(index) => lanes.get(index + 1).copied(),
+                    None => lanes.first().copied(),
+                };
+                ev_set_rally_point.send(SetRallyPointEvent {
+                    building,
+                    start_waypoint: next_lane,
+                });
+            }
+            PanelAction::Remove(removal) => {
+                ev_remove_building.send(RemoveBuildingEvent {
+                    building,
+                    removal: *removal,
+                });
             }
-            _ => {}
         }
     }
 }
 
+#[allow(clippy::type_complexity)]
+fn panel_btn_interaction_handler(
+    mut query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<BtnPanel>)>,
+) {
+    for (interaction, mut background_color) in query.iter_mut() {
+        *background_color = match *interaction {
+            Interaction::Hovered | Interaction::Pressed => Color::rgba(1., 1., 1., 0.2).into(),
+            Interaction::None => Color::rgba(1., 1., 1., 0.05).into(),
+        };
+    }
+}
+
 // --- Helper functions ---
 
+/// Unavailable actions are greyed out. They can still be pressed, to tell the player why they are unavailable.
+fn spawn_panel_btn(
+    builder: &mut ChildBuilder,
+    action: PanelAction,
+    label: String,
+    available: bool,
+) {
+    builder
+        .spawn((
+            BtnPanel { action },
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(6.)),
+                    ..Default::default()
+                },
+                background_color: BackgroundColor(Color::rgba(1., 1., 1., 0.05)),
+                ..Default::default()
+            },
+        ))
+        .with_children(|btn| {
+            btn.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 16.,
+                    color: if available { Color::GOLD } else { Color::GRAY },
+                    ..Default::default()
+                },
+            ));
+        });
+}
+
 fn spawn_building_btn(builder: &mut ChildBuilder, building_blueprint: &BuildingBlueprint) {
     builder
         .spawn((
             BtnBuilding {
                 action: ButtonAction::BuildBuilding(building_blueprint.clone()),
+                // Kept up to date with the buildings the player owns.
+                available: building_blueprint.requires.is_empty(),
             },
             ButtonBundle {
                 style: Style {
@@ -179,5 +814,39 @@ fn spawn_building_btn(builder: &mut ChildBuilder, building_blueprint: &BuildingB
                     ..Default::default()
                 },
             ));
+            // Tier label in the top of the button.
+            btn.spawn(
+                TextBundle::from_section(
+                    format!("T{}", building_blueprint.tier),
+                    TextStyle {
+                        font_size: 14.,
+                        color: Color::WHITE,
+                        ..Default::default()
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(2.),
+                    left: Val::Px(4.),
+                    ..Default::default()
+                }),
+            );
+            // Cost label in the bottom of the button.
+            btn.spawn(
+                TextBundle::from_section(
+                    building_blueprint.cost.to_string(),
+                    TextStyle {
+                        font_size: 16.,
+                        color: Color::GOLD,
+                        ..Default::default()
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(2.),
+                    right: Val::Px(4.),
+                    ..Default::default()
+                }),
+            );
         });
 }
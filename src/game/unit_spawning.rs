@@ -1,10 +1,14 @@
 use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use serde::Deserialize;
 
+use crate::game::construction::UnderConstruction;
+use crate::game::supply::{update_supplies, Supplies};
 use crate::game::teams::Team;
 use crate::game::units::spawn_unit;
 use crate::game::waypoints::WaypointMap;
 use crate::load_game::load_factions::UnitBlueprint;
-use crate::resources::PlayerSettings;
+use crate::resources::TeamSettings;
 
 // --- Plugin ---
 
@@ -14,45 +18,389 @@ pub struct UnitSpawningPlugin<S: States> {
 
 impl<S: States> Plugin for UnitSpawningPlugin<S> {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_event::<SetRallyPointEvent>().add_systems(
             Update,
-            unit_spawner_spawn_units.run_if(in_state(self.state.clone())),
+            (
+                set_rally_points,
+                unit_spawner_spawn_units
+                    .after(update_supplies)
+                    .after(set_rally_points),
+                add_spawner_progress_bars,
+                update_spawner_progress_bars.after(add_spawner_progress_bars),
+            )
+                .run_if(in_state(self.state.clone())),
         );
     }
 }
 
+// --- Constants ---
+
+/// Units spawned in the same cycle are spread out sideways by this distance.
+const SPAWN_SPREAD: f32 = 12.;
+const PROGRESS_BAR_SIZE: Vec2 = Vec2::new(48., 4.);
+/// Where the progress bar is drawn, relative to the spawner.
+const PROGRESS_BAR_OFFSET: Vec3 = Vec3::new(0., 38., 1.);
+
+// --- Types ---
+
+/// A unit a spawner can spawn. Defined in the faction files.
+#[derive(Deserialize, Clone, PartialEq, Debug, Reflect)]
+pub struct SpawnOption {
+    pub unit_id: String,
+    /// How often the unit is spawned compared to the other units of the spawner.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+/// A unit in the production queue of a spawner.
+pub struct SpawnEntry {
+    pub unit_blueprint: UnitBlueprint,
+    pub weight: u32,
+    /// Grows by the weight every time another unit is spawned, so the units take turns by their weights.
+    current_weight: i64,
+}
+
+impl SpawnEntry {
+    pub fn new(unit_blueprint: UnitBlueprint, weight: u32) -> SpawnEntry {
+        SpawnEntry {
+            unit_blueprint,
+            weight,
+            current_weight: 0,
+        }
+    }
+}
+
 // --- Components ---
 
 #[derive(Component)]
 pub struct UnitSpawner {
     pub spawn_time: f32,
     pub time_left: f32,
-    pub unit_blueprint: UnitBlueprint,
+    /// The units spawned in turn. With equal weights, they are spawned in order.
+    pub queue: Vec<SpawnEntry>,
+    pub units_per_cycle: u32,
+    /// Units of the current cycle waiting for room in the supply cap.
+    pub units_waiting: u32,
+}
+
+impl UnitSpawner {
+    /// If all units have a weight of 0, they are spawned in turn as if their weights were equal.
+    pub fn new(spawn_time: f32, mut queue: Vec<SpawnEntry>, units_per_cycle: u32) -> UnitSpawner {
+        if queue.iter().all(|entry| entry.weight == 0) {
+            for entry in queue.iter_mut() {
+                entry.weight = 1;
+            }
+        }
+        UnitSpawner {
+            spawn_time,
+            time_left: spawn_time,
+            queue,
+            units_per_cycle: units_per_cycle.max(1),
+            units_waiting: 0,
+        }
+    }
+
+    /// Progress towards the next units, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        if self.units_waiting > 0 || self.spawn_time <= 0. {
+            return 1.;
+        }
+        (1. - self.time_left / self.spawn_time).clamp(0., 1.)
+    }
+
+    /// The index of the unit to spawn next. It is chosen by smooth weighted round-robin,
+    /// which spreads out the units instead of spawning all of the heaviest first.
+    fn next_index(&self) -> Option<usize> {
+        self.queue
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, entry)| {
+                // The first entry wins ties.
+                (
+                    entry.current_weight + entry.weight as i64,
+                    std::cmp::Reverse(*index),
+                )
+            })
+            .map(|(index, _)| index)
+    }
+
+    pub fn next_unit(&self) -> Option<&UnitBlueprint> {
+        self.next_index()
+            .map(|index| &self.queue[index].unit_blueprint)
+    }
+
+    /// Moves on to the next unit in the queue.
+    fn advance(&mut self) {
+        let Some(index) = self.next_index() else {
+            return;
+        };
+        let total_weight: i64 = self.queue.iter().map(|entry| entry.weight as i64).sum();
+        for entry in self.queue.iter_mut() {
+            entry.current_weight += entry.weight as i64;
+        }
+        self.queue[index].current_weight -= total_weight;
+    }
+}
+
+/// The start waypoint of the lane, which the units spawned by the building follow.
+/// Without one, units take the lane closest to the building.
+#[derive(Component, Default, Reflect)]
+pub struct RallyPoint(pub Option<Entity>);
+
+/// The background of a spawner's progress bar. A child of the spawner.
+#[derive(Component)]
+struct SpawnerProgressBar;
+
+/// The part of the progress bar that fills up. A child of the background.
+#[derive(Component)]
+struct SpawnerProgressFill;
+
+// --- Events ---
+
+/// Chooses the lane of the units spawned by a building. `None` goes back to the closest lane.
+#[derive(Event)]
+pub struct SetRallyPointEvent {
+    pub building: Entity,
+    pub start_waypoint: Option<Entity>,
 }
 
 // --- Systems ---
 
+fn set_rally_points(
+    mut ev_set_rally_point: EventReader<SetRallyPointEvent>,
+    mut query: Query<(&Team, &mut RallyPoint)>,
+    waypoint_map: Res<WaypointMap>,
+) {
+    for ev in ev_set_rally_point.read() {
+        let Ok((team, mut rally_point)) = query.get_mut(ev.building) else {
+            continue;
+        };
+        // Units can only be sent down the lanes of their own team.
+        if let Some(start_waypoint) = ev.start_waypoint {
+            if !waypoint_map.get_lanes(*team).contains(&start_waypoint) {
+                error!(
+                    "{:?} isn't a start waypoint of team {}.",
+                    start_waypoint, team
+                );
+                continue;
+            }
+        }
+        rally_point.0 = ev.start_waypoint;
+    }
+}
+
 fn unit_spawner_spawn_units(
     mut commands: Commands,
     waypoint_map: Res<WaypointMap>,
     time: Res<Time>,
-    mut query: Query<(&mut UnitSpawner, &Transform, &Team)>,
-    player_settings: Res<PlayerSettings>,
+    mut query: Query<
+        (&mut UnitSpawner, &Transform, &Team, Option<&RallyPoint>),
+        // Spawners start once their building is constructed.
+        Without<UnderConstruction>,
+    >,
+    team_settings: Res<TeamSettings>,
+    mut supplies: ResMut<Supplies>,
 ) {
-    for (mut unit_spawner, transform, team) in query.iter_mut() {
-        if unit_spawner.time_left > 0. {
-            unit_spawner.time_left -= time.delta_seconds()
-        } else {
+    for (mut unit_spawner, transform, team, opt_rally_point) in query.iter_mut() {
+        if unit_spawner.units_waiting == 0 {
+            unit_spawner.time_left -= time.delta_seconds();
+            if unit_spawner.time_left > 0. {
+                continue;
+            }
+            unit_spawner.units_waiting = unit_spawner.units_per_cycle;
+        }
+
+        while unit_spawner.units_waiting > 0 {
+            let Some(unit_blueprint) = unit_spawner.next_unit().cloned() else {
+                break;
+            };
+            // Wait with the units ready, until the team has room for them.
+            if !supplies.try_use(*team, unit_blueprint.supply) {
+                break;
+            }
+
+            let spawned = unit_spawner.units_per_cycle - unit_spawner.units_waiting;
+            let spread =
+                (spawned as f32 - (unit_spawner.units_per_cycle - 1) as f32 / 2.) * SPAWN_SPREAD;
             spawn_unit(
                 &mut commands,
                 *team,
-                unit_spawner.unit_blueprint.clone(),
-                transform.translation.x,
+                unit_blueprint,
+                transform.translation.x + spread,
                 transform.translation.y,
+                opt_rally_point.and_then(|rally_point| rally_point.0),
                 &waypoint_map,
-                &player_settings,
+                &team_settings,
             );
-            unit_spawner.time_left = unit_spawner.spawn_time
+            unit_spawner.advance();
+            unit_spawner.units_waiting -= 1;
+        }
+
+        if unit_spawner.units_waiting == 0 {
+            unit_spawner.time_left = unit_spawner.spawn_time;
+        }
+    }
+}
+
+/// Gives spawners a progress bar, and removes it from entities that stopped being spawners.
+fn add_spawner_progress_bars(
+    mut commands: Commands,
+    added_query: Query<(Entity, &Team, Option<&Children>), Added<UnitSpawner>>,
+    mut removed: RemovedComponents<UnitSpawner>,
+    spawner_query: Query<(), With<UnitSpawner>>,
+    children_query: Query<&Children>,
+    bar_query: Query<(), With<SpawnerProgressBar>>,
+    team_settings: Res<TeamSettings>,
+) {
+    for entity in removed.read() {
+        // Spawners replaced by another spawner keep their bar.
+        if spawner_query.contains(entity) {
+            continue;
+        }
+        let Ok(children) = children_query.get(entity) else {
+            continue;
+        };
+        for child in children.iter() {
+            if bar_query.contains(*child) {
+                commands.entity(*child).despawn_recursive();
+            }
         }
     }
+
+    for (entity, team, opt_children) in added_query.iter() {
+        let has_bar = opt_children
+            .is_some_and(|children| children.iter().any(|child| bar_query.contains(*child)));
+        if has_bar {
+            continue;
+        }
+
+        let bar_color = team_settings.color(*team);
+        commands.entity(entity).with_children(|builder| {
+            // The background of the bar, with the fill on top.
+            builder
+                .spawn((
+                    SpawnerProgressBar,
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgba(0., 0., 0., 0.6),
+                            custom_size: Some(PROGRESS_BAR_SIZE),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_translation(PROGRESS_BAR_OFFSET),
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|background| {
+                    background.spawn((
+                        SpawnerProgressFill,
+                        SpriteBundle {
+                            sprite: Sprite {
+                                color: bar_color,
+                                custom_size: Some(Vec2::new(0., PROGRESS_BAR_SIZE.y)),
+                                anchor: Anchor::CenterLeft,
+                                ..Default::default()
+                            },
+                            transform: Transform::from_xyz(-PROGRESS_BAR_SIZE.x / 2., 0., 0.1),
+                            ..Default::default()
+                        },
+                    ));
+                });
+        });
+    }
+}
+
+fn update_spawner_progress_bars(
+    spawner_query: Query<(&UnitSpawner, &Children)>,
+    bar_query: Query<&Children, With<SpawnerProgressBar>>,
+    mut fill_query: Query<&mut Sprite, With<SpawnerProgressFill>>,
+) {
+    for (unit_spawner, children) in spawner_query.iter() {
+        for fill in children
+            .iter()
+            .filter_map(|child| bar_query.get(*child).ok())
+            .flat_map(|bar_children| bar_children.iter())
+        {
+            if let Ok(mut sprite) = fill_query.get_mut(*fill) {
+                sprite.custom_size = Some(Vec2::new(
+                    PROGRESS_BAR_SIZE.x * unit_spawner.progress(),
+                    PROGRESS_BAR_SIZE.y,
+                ));
+            }
+        }
+    }
+}
+
+// --- Helper functions ---
+
+fn default_weight() -> u32 {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(unit_id: &str, weight: u32) -> SpawnEntry {
+        SpawnEntry::new(
+            UnitBlueprint {
+                id: unit_id.to_string(),
+                name: unit_id.to_string(),
+                sprite: Handle::default(),
+                bounty: 0,
+                supply: 1,
+                components: vec![],
+            },
+            weight,
+        )
+    }
+
+    /// The IDs of the next units the spawner spawns.
+    fn spawn_order(unit_spawner: &mut UnitSpawner, count: usize) -> Vec<String> {
+        (0..count)
+            .filter_map(|_| {
+                let unit_id = unit_spawner.next_unit()?.id.clone();
+                unit_spawner.advance();
+                Some(unit_id)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn equal_weights_spawn_in_order() {
+        let mut unit_spawner = UnitSpawner::new(5., vec![entry("a", 1), entry("b", 1)], 1);
+        assert_eq!(spawn_order(&mut unit_spawner, 4), ["a", "b", "a", "b"]);
+    }
+
+    #[test]
+    fn heavier_units_spawn_more_often_but_spread_out() {
+        let mut unit_spawner = UnitSpawner::new(5., vec![entry("a", 2), entry("b", 1)], 1);
+        assert_eq!(
+            spawn_order(&mut unit_spawner, 6),
+            ["a", "b", "a", "a", "b", "a"]
+        );
+    }
+
+    #[test]
+    fn units_with_zero_weight_are_never_spawned() {
+        let mut unit_spawner = UnitSpawner::new(5., vec![entry("a", 0), entry("b", 1)], 1);
+        assert_eq!(spawn_order(&mut unit_spawner, 3), ["b", "b", "b"]);
+    }
+
+    #[test]
+    fn all_zero_weights_spawn_in_turn() {
+        let mut unit_spawner = UnitSpawner::new(5., vec![entry("a", 0), entry("b", 0)], 1);
+        assert_eq!(spawn_order(&mut unit_spawner, 4), ["a", "b", "a", "b"]);
+    }
+
+    #[test]
+    fn empty_queue_spawns_nothing() {
+        let mut unit_spawner = UnitSpawner::new(5., vec![], 1);
+        assert!(spawn_order(&mut unit_spawner, 2).is_empty());
+    }
+
+    #[test]
+    fn at_least_one_unit_per_cycle() {
+        let unit_spawner = UnitSpawner::new(5., vec![entry("a", 1)], 0);
+        assert_eq!(unit_spawner.units_per_cycle, 1);
+    }
 }
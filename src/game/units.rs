@@ -1,34 +1,46 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+use crate::game::attack::BodyRadius;
+use crate::game::economy::Bounty;
 use crate::game::movement::WaypointFollower;
-use crate::game::spawning::add_blueprint_components;
+use crate::game::spawning::{add_blueprint_components, BlueprintId};
+use crate::game::supply::SupplyCost;
 use crate::game::teams::Team;
+use crate::game::veterancy::Veterancy;
 use crate::game::waypoints::WaypointMap;
 use crate::game::InGameTag;
 use crate::load_game::load_factions::UnitBlueprint;
-use crate::resources::PlayerSettings;
+use crate::resources::TeamSettings;
 
 // --- Components ---
 #[derive(Component)]
-struct Unit;
+pub struct Unit;
 
 // --- Helper functions ---
 
 /// Function to help spawn a unit.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_unit(
     commands: &mut Commands,
     team: Team,
     unit_blueprint: UnitBlueprint,
     x: f32,
     y: f32,
+    start_waypoint: Option<Entity>,
     waypoint_map: &Res<WaypointMap>,
-    player_settings: &Res<PlayerSettings>,
+    team_settings: &TeamSettings,
 ) {
+    let Some(faction) = team_settings.faction(team) else {
+        error!("Team {} has no faction to spawn units from.", team);
+        return;
+    };
+
     let mut unit_entity = commands.spawn((
         InGameTag,
         team,
         Unit,
+        BlueprintId(unit_blueprint.id.clone()),
         SpriteBundle {
             transform: Transform::from_xyz(x, y, 10.),
             texture: unit_blueprint.sprite.clone(),
@@ -36,6 +48,9 @@ pub fn spawn_unit(
         },
         RigidBody::KinematicPositionBased,
         Collider::ball(20.), // Actual collider matching sprite size.
+        BodyRadius(20.),
+        Bounty(unit_blueprint.bounty),
+        SupplyCost(unit_blueprint.supply),
     ));
     unit_entity.insert(Name::new(format!(
         "Unit: {} - Team: {}",
@@ -43,13 +58,14 @@ pub fn spawn_unit(
     )));
 
     // Insert components from the blueprint.
-    add_blueprint_components(
-        &mut unit_entity,
-        &unit_blueprint.components,
-        player_settings,
-    );
+    add_blueprint_components(&mut unit_entity, &unit_blueprint.components, faction);
+
+    // Units can only gain experience, if the faction has ranks to promote them to.
+    if !faction.ranks.is_empty() {
+        unit_entity.insert(Veterancy::default());
+    }
 
-    let text_color = team.get_color();
+    let text_color = team_settings.color(team);
 
     unit_entity.with_children(|builder| {
         builder.spawn(Text2dBundle {
@@ -68,7 +84,10 @@ pub fn spawn_unit(
         });
     });
 
-    if let Some(start_waypoint) = waypoint_map.get_closest_start_waypoint(Vec2::new(x, y), team) {
+    // Without a chosen start waypoint, units take the lane closest to where they spawn.
+    if let Some(start_waypoint) =
+        start_waypoint.or_else(|| waypoint_map.get_closest_start_waypoint(Vec2::new(x, y), team))
+    {
         unit_entity.insert(WaypointFollower {
             waypoint: start_waypoint,
         });
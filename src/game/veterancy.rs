@@ -0,0 +1,231 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::attack::AttackStats;
+use crate::game::damage::{apply_damage, DamageEvent, DeathEvent};
+use crate::game::health::{check_death, Health};
+use crate::game::movement::MovementSpeed;
+use crate::game::teams::Team;
+use crate::resources::TeamSettings;
+
+/*
+Units gain experience by dealing damage and killing enemies.
+Once they have enough experience, they are promoted to the next rank of their faction,
+which makes them stronger and gives them a rank badge.
+*/
+
+// --- Plugin ---
+
+pub struct VeterancyPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for VeterancyPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (gain_experience, promote_units)
+                .chain()
+                .after(apply_damage)
+                .before(check_death)
+                .run_if(in_state(self.state.clone())),
+        );
+    }
+}
+
+// --- Constants ---
+
+/// Experience gained for each point of damage dealt to enemies, before armor.
+const EXPERIENCE_PER_DAMAGE: u32 = 1;
+/// Experience gained for the killing blow.
+const EXPERIENCE_PER_KILL: u32 = 10;
+
+// --- Types ---
+
+/// A rank units can be promoted to. Defined in the faction files.
+#[derive(Deserialize, Debug, Clone, PartialEq, Reflect)]
+pub struct RankBlueprint {
+    pub name: String,
+    /// The total experience needed to be promoted to the rank.
+    pub experience: u32,
+    /// Multipliers on the stats the unit had without any rank.
+    #[serde(default = "default_multiplier")]
+    pub health_multiplier: f32,
+    #[serde(default = "default_multiplier")]
+    pub damage_multiplier: f32,
+    #[serde(default = "default_multiplier")]
+    pub attack_speed_multiplier: f32,
+    #[serde(default = "default_multiplier")]
+    pub movement_speed_multiplier: f32,
+}
+
+// --- Components ---
+
+#[derive(Component, Debug, Default, Reflect)]
+pub struct Veterancy {
+    pub experience: u32,
+    /// How many times the unit has been promoted. Zero is no rank.
+    pub rank: usize,
+}
+
+/// Shows the name of the rank of a unit.
+#[derive(Component)]
+struct RankBadge;
+
+// --- Systems ---
+
+/// Gives experience to the sources of damage to enemies.
+fn gain_experience(
+    mut ev_damage: EventReader<DamageEvent>,
+    mut ev_death: EventReader<DeathEvent>,
+    team_query: Query<&Team>,
+    mut veterancy_query: Query<&mut Veterancy>,
+) {
+    for event in ev_damage.read() {
+        let Some(source) = event.source else {
+            continue;
+        };
+        // No experience for friendly fire.
+        if !team_query
+            .get(event.target)
+            .is_ok_and(|target_team| *target_team != source.team)
+        {
+            continue;
+        }
+        if let Ok(mut veterancy) = veterancy_query.get_mut(source.entity) {
+            veterancy.experience += event.amount.max(0) as u32 * EXPERIENCE_PER_DAMAGE;
+        }
+    }
+
+    for event in ev_death.read() {
+        let Some(killer) = event.killer else {
+            continue;
+        };
+        if killer.team == event.team {
+            continue;
+        }
+        if let Ok(mut veterancy) = veterancy_query.get_mut(killer.entity) {
+            veterancy.experience += EXPERIENCE_PER_KILL;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn promote_units(
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &Team,
+            &mut Veterancy,
+            Option<&mut Health>,
+            Option<&mut AttackStats>,
+            Option<&mut MovementSpeed>,
+            Option<&Children>,
+        ),
+        Changed<Veterancy>,
+    >,
+    mut badge_query: Query<&mut Text, With<RankBadge>>,
+    team_settings: Res<TeamSettings>,
+) {
+    for (
+        entity,
+        team,
+        mut veterancy,
+        mut opt_health,
+        mut opt_attack_stats,
+        mut opt_movement_speed,
+        opt_children,
+    ) in query.iter_mut()
+    {
+        let Some(faction) = team_settings.faction(*team) else {
+            continue;
+        };
+
+        // Skip ranks, if enough experience was gained at once.
+        let mut new_rank = veterancy.rank;
+        while faction
+            .ranks
+            .get(new_rank)
+            .is_some_and(|rank| veterancy.experience >= rank.experience)
+        {
+            new_rank += 1;
+        }
+        if new_rank == veterancy.rank {
+            continue;
+        }
+        let rank = &faction.ranks[new_rank - 1];
+
+        // Only scale by the difference to the previous rank, so upgrades researched in between are kept.
+        let previous_rank = veterancy
+            .rank
+            .checked_sub(1)
+            .and_then(|index| faction.ranks.get(index));
+        let ratio = |multiplier: fn(&RankBlueprint) -> f32| {
+            multiplier(rank) / previous_rank.map_or(1., multiplier)
+        };
+
+        if let Some(health) = &mut opt_health {
+            let max_health =
+                (health.max_health as f32 * ratio(|rank| rank.health_multiplier)).round() as i32;
+            // Keep the damage taken, so the unit gains the added health.
+            health.health += max_health - health.max_health;
+            health.max_health = max_health;
+        }
+        if let Some(attack_stats) = &mut opt_attack_stats {
+            attack_stats.damage =
+                (attack_stats.damage as f32 * ratio(|rank| rank.damage_multiplier)).round() as i32;
+            attack_stats.attack_speed *= ratio(|rank| rank.attack_speed_multiplier);
+        }
+        if let Some(movement_speed) = &mut opt_movement_speed {
+            movement_speed.0 *= ratio(|rank| rank.movement_speed_multiplier);
+        }
+
+        veterancy.rank = new_rank;
+        info!("{:?} was promoted to {}!", entity, rank.name);
+
+        // Update the badge, or give the unit one at its first promotion.
+        let existing_badge = opt_children.and_then(|children| {
+            children
+                .iter()
+                .copied()
+                .find(|child| badge_query.contains(*child))
+        });
+        match existing_badge {
+            Some(badge) => {
+                if let Ok(mut text) = badge_query.get_mut(badge) {
+                    text.sections[0].value.clone_from(&rank.name);
+                }
+            }
+            None => {
+                let text_color = team_settings.color(*team);
+                commands.entity(entity).with_children(|builder| {
+                    builder.spawn((
+                        RankBadge,
+                        Text2dBundle {
+                            text: Text {
+                                sections: vec![TextSection::new(
+                                    rank.name.clone(),
+                                    TextStyle {
+                                        font_size: 14.,
+                                        color: text_color,
+                                        ..Default::default()
+                                    },
+                                )],
+                                ..Default::default()
+                            },
+                            transform: Transform::from_translation(Vec3::new(0.0, 22.0, 1.0)),
+                            ..Default::default()
+                        },
+                    ));
+                });
+            }
+        }
+    }
+}
+
+// --- Helper functions ---
+
+fn default_multiplier() -> f32 {
+    1.
+}
@@ -34,7 +34,7 @@ impl<S: States> Plugin for VisionPlugin<S> {
 // --- Types ---
 
 // type alias for easier usage later
-type TeamEntityTree = KDTree2<Team>;
+pub type TeamEntityTree = KDTree2<Team>;
 
 // --- Components ---
 
@@ -42,14 +42,28 @@ impl WaypointMap {
                 .iter()
                 .min_by(|a, b| {
                     a.1.distance_squared(current_position)
-                        .partial_cmp(&b.1.distance_squared(current_position))
-                        .unwrap()
+                        .total_cmp(&b.1.distance_squared(current_position))
                 })
                 .map(|(entity, _)| *entity)
         } else {
             None // Return None, if the team has no waypoints.
         }
     }
+
+    /// The start waypoints of a team, ordered from the top of the map to the bottom.
+    /// Each start waypoint begins a lane.
+    pub fn get_lanes(&self, team: Team) -> Vec<Entity> {
+        let mut start_waypoints = self
+            .start_point_waypoints
+            .get(&team)
+            .cloned()
+            .unwrap_or_default();
+        start_waypoints.sort_by(|a, b| b.1.y.total_cmp(&a.1.y));
+        start_waypoints
+            .into_iter()
+            .map(|(entity, _)| entity)
+            .collect()
+    }
 }
 
 #[derive(Default, Component, Reflect)]
@@ -65,6 +79,11 @@ impl IsStartPoint {
     }
 }
 
+/// The start waypoints of a match are despawned with its level, so the next match starts without them.
+pub fn clear_waypoint_map(mut waypoint_map: ResMut<WaypointMap>) {
+    waypoint_map.start_point_waypoints.clear();
+}
+
 fn add_start_waypoints_to_resources(
     query: Query<(Entity, &TeamAssociation, &Transform, &IsStartPoint), Added<Waypoint>>,
     mut waypoint_map: ResMut<WaypointMap>,
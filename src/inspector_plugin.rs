@@ -3,9 +3,19 @@ use bevy::input::common_conditions::input_toggle_active;
 use bevy::prelude::KeyCode;
 use bevy_inspector_egui::quick::{ResourceInspectorPlugin, WorldInspectorPlugin};
 
+use crate::game::attack::BodyRadius;
+use crate::game::auras::{AuraBuffs, Auras};
+use crate::game::damage::Defense;
+use crate::game::economy::{Bounty, Treasuries};
+use crate::game::healing::Healer;
 use crate::game::health::Health;
 use crate::game::movement::{MoveTarget, MoveToPoint, WaypointFollower};
+use crate::game::splash::SplashDamage;
+use crate::game::status_effects::{OnHitEffects, StatusEffects};
+use crate::game::supply::{Supplies, SupplyCost, SupplyProvider};
+use crate::game::targeting::{AttackCandidates, TargetingRules};
 use crate::game::teams::Team;
+use crate::game::veterancy::Veterancy;
 use crate::game::vision::{InVision, VisionRange};
 use crate::game::waypoints::{IsStartPoint, Waypoint, WaypointMap};
 use crate::load_game::load_factions::Factions;
@@ -20,6 +30,10 @@ impl Plugin for InspectorPlugin {
                 .run_if(input_toggle_active(true, KeyCode::Escape)),
             ResourceInspectorPlugin::<Factions>::default()
                 .run_if(input_toggle_active(true, KeyCode::Escape)),
+            ResourceInspectorPlugin::<Treasuries>::default()
+                .run_if(input_toggle_active(true, KeyCode::Escape)),
+            ResourceInspectorPlugin::<Supplies>::default()
+                .run_if(input_toggle_active(true, KeyCode::Escape)),
         ))
         // Types.
         .register_type::<Team>()
@@ -27,8 +41,22 @@ impl Plugin for InspectorPlugin {
         .register_type::<Waypoint>()
         .register_type::<WaypointFollower>()
         .register_type::<Health>()
+        .register_type::<Healer>()
+        .register_type::<Auras>()
+        .register_type::<AuraBuffs>()
+        .register_type::<Defense>()
+        .register_type::<BodyRadius>()
+        .register_type::<Bounty>()
+        .register_type::<SupplyCost>()
+        .register_type::<SupplyProvider>()
+        .register_type::<AttackCandidates>()
+        .register_type::<TargetingRules>()
+        .register_type::<SplashDamage>()
+        .register_type::<OnHitEffects>()
+        .register_type::<StatusEffects>()
         .register_type::<MoveTarget>()
         .register_type::<MoveToPoint>()
+        .register_type::<Veterancy>()
         .register_type::<VisionRange>()
         .register_type::<InVision>();
     }
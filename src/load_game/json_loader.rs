@@ -0,0 +1,79 @@
+// Most of this code was taken from the bevy_common_assets crate:
+// https://github.com/NiklasEi/bevy_common_assets/blob/main/src/json.rs
+// There are also examples of the same with JSON, RON, TOML and more if we want to switch later.
+
+use std::marker::PhantomData;
+
+use bevy::asset::io::Reader;
+use bevy::asset::*;
+use serde::de::DeserializeOwned;
+use serde_json::from_slice;
+use thiserror::Error;
+
+// --- Custom Asset Loader ---
+// Read more on custom asset loaders under `Custom asset loader` here:
+// https://taintedcoders.com/bevy/assets/
+
+/// An asset loaded from json files.
+pub trait JsonAsset: Asset + DeserializeOwned {
+    /// Files ending with one of these are loaded as this asset.
+    const EXTENSIONS: &'static [&'static str];
+
+    /// Checks the asset once it has been parsed. Assets failing the check aren't loaded.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Loads the asset type `A` from json files.
+pub struct JsonAssetLoader<A> {
+    _marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Default for JsonAssetLoader<A> {
+    fn default() -> Self {
+        JsonAssetLoader {
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Possible errors that can be produced by [`JsonAssetLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum JsonLoaderError {
+    /// An [IO Error](std::io::Error)
+    #[error("Could not read the file: {0}")]
+    Io(#[from] std::io::Error),
+    /// A [JSON Error](serde_json::Error)
+    #[error("Could not parse JSON: {0}")]
+    JsonError(#[from] serde_json::Error),
+    /// The asset was parsed, but failed [`JsonAsset::validate`].
+    #[error("Invalid asset: {0}")]
+    Invalid(String),
+}
+
+impl<A: JsonAsset> AssetLoader for JsonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = JsonLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let asset = from_slice::<A>(&bytes)?;
+            asset.validate().map_err(JsonLoaderError::Invalid)?;
+            Ok(asset)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        A::EXTENSIONS
+    }
+}
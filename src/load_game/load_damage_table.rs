@@ -0,0 +1,84 @@
+use bevy::app::{App, Plugin};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+
+use crate::game::damage::{ArmorType, DamageType};
+use crate::load_game::json_loader::{JsonAsset, JsonAssetLoader};
+use crate::load_game::LoadingSet::{LoadStartup, LoadUpdate};
+use crate::AppState;
+
+/// Plugin to load the [`DamageTableAsset`] from a json file.
+pub struct DamageTableLoaderPlugin;
+
+impl Plugin for DamageTableLoaderPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_asset_loader(JsonAssetLoader::<DamageTableAsset>::default())
+            .init_asset::<DamageTableAsset>()
+            .add_systems(
+                OnEnter(AppState::LoadGameAssets),
+                load_damage_table.in_set(LoadStartup),
+            )
+            .add_systems(Update, setup_damage_table_resource.in_set(LoadUpdate));
+    }
+}
+
+// --- Asset types ---
+
+/// How much of the damage of each damage type is dealt to each armor type.
+#[derive(Deserialize, Debug, Clone, TypePath, Asset)]
+pub struct DamageTableAsset(HashMap<DamageType, HashMap<ArmorType, f32>>);
+
+impl JsonAsset for DamageTableAsset {
+    const EXTENSIONS: &'static [&'static str] = &["damage_table.json"];
+}
+
+// --- Resources ---
+
+#[derive(Resource)]
+struct DamageTableHandle(Handle<DamageTableAsset>);
+
+#[derive(Resource, Debug, Clone, Default, Reflect)]
+pub struct DamageTable(HashMap<DamageType, HashMap<ArmorType, f32>>);
+
+impl From<HashMap<DamageType, HashMap<ArmorType, f32>>> for DamageTable {
+    fn from(multipliers: HashMap<DamageType, HashMap<ArmorType, f32>>) -> Self {
+        DamageTable(multipliers)
+    }
+}
+
+impl DamageTable {
+    /// Combinations missing from the table deal full damage.
+    pub fn multiplier(&self, damage_type: DamageType, armor_type: ArmorType) -> f32 {
+        self.0
+            .get(&damage_type)
+            .and_then(|armor_multipliers| armor_multipliers.get(&armor_type))
+            .copied()
+            .unwrap_or(1.)
+    }
+}
+
+// --- Systems ---
+
+/// Starts loading the global damage table.
+fn load_damage_table(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let handle = asset_server.load("global.damage_table.json");
+    commands.insert_resource(DamageTableHandle(handle));
+}
+
+/// Adds the damage table to resources, once it has been loaded.
+fn setup_damage_table_resource(
+    mut commands: Commands,
+    damage_table_handle: Res<DamageTableHandle>,
+    mut events: EventReader<AssetEvent<DamageTableAsset>>,
+    damage_tables: Res<Assets<DamageTableAsset>>,
+) {
+    for event in events.read() {
+        if event.is_loaded_with_dependencies(&damage_table_handle.0) {
+            if let Some(damage_table) = damage_tables.get(&damage_table_handle.0) {
+                commands.insert_resource(DamageTable::from(damage_table.0.clone()));
+                info!("Damage table resource has been inserted!");
+            }
+        }
+    }
+}
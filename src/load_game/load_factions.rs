@@ -1,16 +1,19 @@
-// Most of this code was taken from the bevy_common_assets crate:
-// https://github.com/NiklasEi/bevy_common_assets/blob/main/src/json.rs
-// There are also examples of the same with JSON, RON, TOML and more if we want to switch later.
-
 use bevy::app::{App, Plugin};
-use bevy::asset::io::Reader;
-use bevy::asset::*;
+use bevy::asset::LoadedFolder;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 use serde::Deserialize;
-use serde_json::from_slice;
-use thiserror::Error;
 
+use crate::game::auras::Aura;
+use crate::game::building_upgrades::BuildingUpgradeBlueprint;
+use crate::game::damage::{ArmorType, DamageType};
+use crate::game::research::UpgradeBlueprint;
+use crate::game::status_effects::StatusEffectBlueprint;
+use crate::game::targeting::TargetRule;
+use crate::game::unit_spawning::SpawnOption;
+use crate::game::veterancy::RankBlueprint;
+use crate::load_game::json_loader::{JsonAsset, JsonAssetLoader};
+use crate::load_game::load_damage_table::DamageTable;
 use crate::load_game::LoadingSet::{LoadStartup, LoadUpdate};
 use crate::AppState;
 
@@ -19,7 +22,7 @@ pub struct FactionLoaderPlugin;
 
 impl Plugin for FactionLoaderPlugin {
     fn build(&self, app: &mut App) {
-        app.register_asset_loader::<FactionAssetLoader>(FactionAssetLoader)
+        app.register_asset_loader(JsonAssetLoader::<FactionAsset>::default())
             .init_asset::<FactionAsset>()
             .add_systems(
                 OnEnter(AppState::LoadGameAssets),
@@ -34,50 +37,6 @@ impl Plugin for FactionLoaderPlugin {
     }
 }
 
-// --- Custom Asset Loader ---
-// Read more on custom asset loaders under `Custom asset loader` here:
-// https://taintedcoders.com/bevy/assets/
-
-/// Loads your asset type [`FactionAsset`] from json files.
-pub struct FactionAssetLoader;
-
-/// Possible errors that can be produced by [`FactionAssetLoader`]
-#[non_exhaustive]
-#[derive(Debug, Error)]
-pub enum FactionLoaderError {
-    /// An [IO Error](std::io::Error)
-    #[error("Could not read the file: {0}")]
-    Io(#[from] std::io::Error),
-    /// A [JSON Error](serde_json::Error)
-    #[error("Could not parse JSON: {0}")]
-    JsonError(#[from] serde_json::Error),
-}
-
-impl AssetLoader for FactionAssetLoader {
-    type Asset = FactionAsset;
-    type Settings = ();
-    type Error = FactionLoaderError;
-
-    fn load<'a>(
-        &'a self,
-        reader: &'a mut Reader,
-        _settings: &'a (),
-        _load_context: &'a mut LoadContext,
-    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
-        Box::pin(async move {
-            let mut bytes = Vec::new();
-            reader.read_to_end(&mut bytes).await?;
-            let asset = from_slice::<FactionAsset>(&bytes)?;
-            Ok(asset)
-        })
-    }
-
-    fn extensions(&self) -> &[&str] {
-        // Use this loader for files ending with faction.json
-        &["faction.json"]
-    }
-}
-
 // --- Asset types ---
 
 #[derive(Deserialize, Debug, Clone, TypePath, Asset)]
@@ -86,6 +45,72 @@ pub struct FactionAsset {
     name: String,
     buildings: Vec<BuildingData>,
     units: Vec<UnitData>,
+    #[serde(default)]
+    ranks: Vec<RankBlueprint>,
+    #[serde(default)]
+    upgrades: Vec<UpgradeBlueprint>,
+}
+
+impl JsonAsset for FactionAsset {
+    const EXTENSIONS: &'static [&'static str] = &["faction.json"];
+
+    /// Rejects attack speeds that aren't above zero, as the time between attacks is one over the attack speed.
+    /// Rejects negative times, which timers can't be made from.
+    fn validate(&self) -> Result<(), String> {
+        for building in self.buildings.iter() {
+            for upgrade in building.upgrades_to.iter() {
+                self.check_time(&building.id, "an upgrade time", upgrade.upgrade_time)?;
+            }
+        }
+        for upgrade in self.upgrades.iter() {
+            self.check_time(&upgrade.id, "a research time", upgrade.research_time)?;
+        }
+
+        let entities = self
+            .buildings
+            .iter()
+            .map(|building| (&building.id, &building.components))
+            .chain(self.units.iter().map(|unit| (&unit.id, &unit.components)));
+        for (id, components) in entities {
+            for component in components.iter() {
+                match component {
+                    ComponentBlueprint::AttackStats { attack_speed, .. }
+                        if *attack_speed <= 0. || !attack_speed.is_finite() =>
+                    {
+                        return Err(format!(
+                            "{:?} of faction {:?} has an attack speed of {}, but it must be above 0.",
+                            id, self.id, attack_speed
+                        ));
+                    }
+                    ComponentBlueprint::UnitSpawner { spawn_time, .. } => {
+                        self.check_time(id, "a spawn time", *spawn_time)?;
+                    }
+                    ComponentBlueprint::Healer { cooldown, .. } => {
+                        self.check_time(id, "a heal cooldown", *cooldown)?;
+                    }
+                    ComponentBlueprint::OnHit(effects) => {
+                        for effect in effects.iter() {
+                            self.check_time(id, "a status effect duration", effect.duration)?;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FactionAsset {
+    fn check_time(&self, id: &str, time_name: &str, seconds: f32) -> Result<(), String> {
+        if seconds < 0. || !seconds.is_finite() {
+            return Err(format!(
+                "{:?} of faction {:?} has {} of {}, but it must be 0 or more.",
+                id, self.id, time_name, seconds
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -94,6 +119,23 @@ struct BuildingData {
     name: String,
     sprite: String,
     icon: String,
+    cost: u32,
+    #[serde(default)]
+    bounty: u32,
+    #[serde(default = "default_tier")]
+    tier: u32,
+    #[serde(default)]
+    requires: Vec<String>,
+    #[serde(default = "default_buildable")]
+    buildable: bool,
+    #[serde(default = "default_sell_refund")]
+    sell_refund: f32,
+    #[serde(default = "default_cancel_refund")]
+    cancel_refund: f32,
+    #[serde(default)]
+    build_time: f32,
+    #[serde(default)]
+    upgrades_to: Vec<BuildingUpgradeBlueprint>,
     components: Vec<ComponentBlueprint>,
 }
 
@@ -102,6 +144,10 @@ struct UnitData {
     id: String,
     name: String,
     sprite: String,
+    #[serde(default)]
+    bounty: u32,
+    #[serde(default = "default_supply")]
+    supply: u32,
     components: Vec<ComponentBlueprint>,
 }
 
@@ -113,6 +159,9 @@ pub struct FactionBlueprint {
     pub name: String,
     pub buildings: HashMap<String, BuildingBlueprint>,
     pub units: HashMap<String, UnitBlueprint>,
+    /// The ranks units are promoted to with experience, from lowest to highest.
+    pub ranks: Vec<RankBlueprint>,
+    pub upgrades: HashMap<String, UpgradeBlueprint>,
 }
 
 #[derive(Debug, Clone, Reflect)]
@@ -121,22 +170,58 @@ pub struct BuildingBlueprint {
     pub name: String,
     pub sprite: Handle<Image>,
     pub icon: Handle<Image>,
+    /// Gold it costs to place the building.
+    pub cost: u32,
+    /// Gold given to the team that destroys the building.
+    pub bounty: u32,
+    /// How far into the match the building is meant to be available. Buildings of the lowest tier come first.
+    pub tier: u32,
+    /// IDs of the buildings the team must own, before it can place this building.
+    pub requires: Vec<String>,
+    /// If the building can be placed. Buildings only reached by upgrading another building can't.
+    pub buildable: bool,
+    /// The share of the cost refunded when the building is sold at full health.
+    pub sell_refund: f32,
+    /// The share of the cost refunded when the construction of the building is cancelled.
+    pub cancel_refund: f32,
+    /// Seconds it takes to construct the building after it is placed. Built instantly if zero.
+    pub build_time: f32,
+    /// The buildings this building can be upgraded into.
+    pub upgrades_to: Vec<BuildingUpgradeBlueprint>,
     pub components: Vec<ComponentBlueprint>,
 }
 
+impl BuildingBlueprint {
+    /// The first required building, which the team doesn't own any of.
+    pub fn missing_requirement(&self, owned_buildings: &HashMap<&str, usize>) -> Option<&str> {
+        self.requires
+            .iter()
+            .map(String::as_str)
+            .find(|required| owned_buildings.get(required).copied().unwrap_or(0) == 0)
+    }
+}
+
 #[derive(Debug, Clone, Reflect)]
 pub struct UnitBlueprint {
     pub id: String,
     pub name: String,
     pub sprite: Handle<Image>,
+    /// Gold given to the team that kills the unit.
+    pub bounty: u32,
+    /// How much of the team's supply cap the unit uses.
+    pub supply: u32,
     pub components: Vec<ComponentBlueprint>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Clone, Reflect)]
 pub enum ComponentBlueprint {
+    /// Spawns the units in turn, by their weights.
     UnitSpawner {
-        unit_id: String,
+        units: Vec<SpawnOption>,
         spawn_time: f32,
+        /// How many units are spawned at the end of each spawn time.
+        #[serde(default = "default_units_per_cycle")]
+        units_per_cycle: u32,
     },
     Health {
         max_health: i32,
@@ -144,10 +229,55 @@ pub enum ComponentBlueprint {
     },
     AttackStats {
         damage: i32,
+        #[serde(default)]
+        damage_type: DamageType,
         attack_speed: f32,
         attack_range: i32,
     },
+    Defense {
+        armor_type: ArmorType,
+        armor: i32,
+    },
+    /// Buffs given to friendlies within the radius of each aura.
+    Auras(Vec<Aura>),
+    /// Heals the most damaged friendly in range. Needs `VisionRange` to find friendlies.
+    Healer {
+        heal_amount: i32,
+        cooldown: f32,
+        heal_range: f32,
+    },
+    /// The rules for choosing attack targets, in order of importance.
+    Targeting(Vec<TargetRule>),
     VisionRange(f32),
+    /// Attacks fire projectiles, which deal the damage on impact.
+    Projectile {
+        speed: f32,
+        /// Projectiles without a sprite are drawn as a rectangle in the team color.
+        #[serde(default)]
+        sprite: Option<String>,
+        /// Loaded from `sprite`, when the faction is set up.
+        #[serde(skip)]
+        sprite_handle: Option<Handle<Image>>,
+        /// The size to draw the projectile at. Defaults to the size of the sprite.
+        #[serde(default)]
+        size: Option<(f32, f32)>,
+        #[serde(default)]
+        homing: bool,
+        #[serde(default)]
+        arc_height: f32,
+    },
+    /// Attacks also damage everything within the radius of the point of impact.
+    Splash {
+        radius: f32,
+        #[serde(default)]
+        falloff: f32,
+        #[serde(default)]
+        friendly_fire: bool,
+    },
+    /// Status effects applied to the targets hit by attacks.
+    OnHit(Vec<StatusEffectBlueprint>),
+    /// Raises the supply cap of the team.
+    Supply(u32),
     OpponentFollower,
     MovementSpeed(i32),
     Visible,
@@ -195,7 +325,7 @@ fn setup_factions_resource(
                         continue;
                     };
 
-                    let faction_blueprint = FactionBlueprint {
+                    let mut faction_blueprint = FactionBlueprint {
                         id: faction.id.clone(),
                         name: faction.name.clone(),
                         buildings: faction
@@ -209,7 +339,19 @@ fn setup_factions_resource(
                                         name: building_asset.name.clone(),
                                         sprite: asset_server.load(&building_asset.sprite),
                                         icon: asset_server.load(&building_asset.icon),
-                                        components: building_asset.components.clone(),
+                                        cost: building_asset.cost,
+                                        bounty: building_asset.bounty,
+                                        tier: building_asset.tier,
+                                        requires: building_asset.requires.clone(),
+                                        buildable: building_asset.buildable,
+                                        sell_refund: building_asset.sell_refund,
+                                        cancel_refund: building_asset.cancel_refund,
+                                        build_time: building_asset.build_time,
+                                        upgrades_to: building_asset.upgrades_to.clone(),
+                                        components: load_component_assets(
+                                            &building_asset.components,
+                                            &asset_server,
+                                        ),
                                     },
                                 )
                             })
@@ -224,12 +366,24 @@ fn setup_factions_resource(
                                         id: unit_asset.id.clone(),
                                         name: unit_asset.name.clone(),
                                         sprite: asset_server.load(&unit_asset.sprite),
-                                        components: unit_asset.components.clone(),
+                                        bounty: unit_asset.bounty,
+                                        supply: unit_asset.supply,
+                                        components: load_component_assets(
+                                            &unit_asset.components,
+                                            &asset_server,
+                                        ),
                                     },
                                 )
                             })
                             .collect(),
+                        ranks: faction.ranks.clone(),
+                        upgrades: faction
+                            .upgrades
+                            .iter()
+                            .map(|upgrade| (upgrade.id.clone(), upgrade.clone()))
+                            .collect(),
                     };
+                    validate_faction(&mut faction_blueprint);
 
                     faction_blueprints.push(faction_blueprint);
                 }
@@ -241,12 +395,112 @@ fn setup_factions_resource(
     }
 }
 
+/// Starts loading the assets referenced by component blueprints.
+fn load_component_assets(
+    component_blueprints: &[ComponentBlueprint],
+    asset_server: &AssetServer,
+) -> Vec<ComponentBlueprint> {
+    component_blueprints
+        .iter()
+        .cloned()
+        .map(|mut component_blueprint| {
+            if let ComponentBlueprint::Projectile {
+                sprite: Some(sprite),
+                sprite_handle,
+                ..
+            } = &mut component_blueprint
+            {
+                *sprite_handle = Some(asset_server.load(sprite.clone()));
+            }
+            component_blueprint
+        })
+        .collect()
+}
+
+/// Warns about buildings and upgrades referring to buildings, units or upgrades the faction doesn't have.
+/// Unknown required buildings are dropped, as they could never be built.
+fn validate_faction(faction: &mut FactionBlueprint) {
+    let building_ids: Vec<String> = faction.buildings.keys().cloned().collect();
+    for building in faction.buildings.values_mut() {
+        building.requires.retain(|required| {
+            let known = building_ids.contains(required);
+            if !known {
+                error!(
+                    "Building {:?} of faction {:?} requires the unknown building {:?}. The requirement is dropped.",
+                    building.id, faction.id, required
+                );
+            }
+            known
+        });
+    }
+
+    for building in faction.buildings.values() {
+        for upgrade in building.upgrades_to.iter() {
+            if !faction.buildings.contains_key(&upgrade.building_id) {
+                warn!(
+                    "Building {:?} of faction {:?} upgrades into the unknown building {:?}.",
+                    building.id, faction.id, upgrade.building_id
+                );
+            }
+        }
+    }
+    for upgrade in faction.upgrades.values() {
+        if !faction.buildings.contains_key(&upgrade.researched_at) {
+            warn!(
+                "Upgrade {:?} of faction {:?} is researched at the unknown building {:?}.",
+                upgrade.id, faction.id, upgrade.researched_at
+            );
+        }
+        for target in upgrade.targets.iter() {
+            if !faction.buildings.contains_key(target) && !faction.units.contains_key(target) {
+                warn!(
+                    "Upgrade {:?} of faction {:?} targets the unknown unit or building {:?}.",
+                    upgrade.id, faction.id, target
+                );
+            }
+        }
+        for required in upgrade.requires.iter() {
+            if !faction.upgrades.contains_key(required) {
+                warn!(
+                    "Upgrade {:?} of faction {:?} requires the unknown upgrade {:?}.",
+                    upgrade.id, faction.id, required
+                );
+            }
+        }
+    }
+}
+
+fn default_tier() -> u32 {
+    1
+}
+
+fn default_buildable() -> bool {
+    true
+}
+
+fn default_sell_refund() -> f32 {
+    0.5
+}
+
+fn default_cancel_refund() -> f32 {
+    0.75
+}
+
+fn default_supply() -> u32 {
+    1
+}
+
+fn default_units_per_cycle() -> u32 {
+    1
+}
+
 /// This is a test function to see that it works. When factions are in use, this should be removed.
 fn display_content(
     factions_res: Option<Res<Factions>>,
+    damage_table_res: Option<Res<DamageTable>>,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
-    let Some(factions) = factions_res else {
+    let (Some(factions), Some(_)) = (factions_res, damage_table_res) else {
         return;
     };
 
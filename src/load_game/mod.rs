@@ -1,9 +1,12 @@
 use bevy::prelude::*;
 
+use crate::load_game::load_damage_table::DamageTableLoaderPlugin;
 use crate::load_game::load_factions::FactionLoaderPlugin;
 use crate::load_game::LoadingSet::{LoadStartup, LoadUpdate};
 use crate::AppState;
 
+pub mod json_loader;
+pub mod load_damage_table;
 pub mod load_factions;
 
 pub struct LoadGamePlugin;
@@ -20,7 +23,7 @@ impl Plugin for LoadGamePlugin {
                     .after(LoadStartup),
             )
             // Plugins.
-            .add_plugins(FactionLoaderPlugin)
+            .add_plugins((FactionLoaderPlugin, DamageTableLoaderPlugin))
             // Third party plugins.
             // Third party resources
             // State Transitions
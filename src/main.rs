@@ -15,6 +15,7 @@ mod inspector_plugin;
 mod load_game;
 mod main_menu;
 mod resources;
+mod styles;
 mod systems;
 
 fn main() {
@@ -2,11 +2,8 @@
 
 use bevy::prelude::*;
 
-use crate::game::teams::Team;
-use crate::load_game::load_factions::Factions;
-use crate::main_menu::MainMenuTag;
-use crate::resources::PlayerSettings;
-use crate::AppState;
+use crate::main_menu::{despawn_screen, MainMenuTag, MenuScreen};
+use crate::styles::{HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON};
 
 pub struct InitScreenPlugin<S: States> {
     pub state: S,
@@ -15,6 +12,7 @@ pub struct InitScreenPlugin<S: States> {
 impl<S: States> Plugin for InitScreenPlugin<S> {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(self.state.clone()), spawn_ui)
+            .add_systems(OnExit(self.state.clone()), despawn_screen::<InitScreenTag>)
             .add_systems(
                 Update,
                 (btn_interaction_handler, btn_action_handler).run_if(in_state(self.state.clone())),
@@ -22,14 +20,11 @@ impl<S: States> Plugin for InitScreenPlugin<S> {
     }
 }
 
-// --- Constants ---
-
-const NORMAL_BUTTON: Color = Color::rgba(1., 1., 1., 0.2);
-const HOVERED_BUTTON: Color = Color::rgba(1., 1., 1., 0.3);
-const PRESSED_BUTTON: Color = Color::rgba(1., 1., 1., 0.5);
-
 // --- Components ---
 
+#[derive(Component)]
+struct InitScreenTag;
+
 #[derive(Component)]
 struct Label;
 
@@ -53,6 +48,7 @@ fn spawn_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands
         .spawn((
             MainMenuTag,
+            InitScreenTag,
             NodeBundle {
                 style: Style {
                     width: Val::Percent(100.),
@@ -117,29 +113,13 @@ fn spawn_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 fn btn_action_handler(
-    mut commands: Commands,
     query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
-    mut next_state: ResMut<NextState<AppState>>,
-    loaded_factions: Option<Res<Factions>>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
 ) {
     for (interaction, menu_button) in query.iter() {
         match *interaction {
             Interaction::Pressed => match menu_button.action {
-                ButtonAction::Play => {
-                    if let Some(factions) = &loaded_factions {
-                        if let Some(selected_faction) = factions.0.first().cloned() {
-                            commands.insert_resource(PlayerSettings {
-                                team: Team::Red,
-                                faction: selected_faction,
-                            });
-                            next_state.set(AppState::Game)
-                        } else {
-                            error!("Couldn't get a faction from loaded factions to set as the selected faction.")
-                        }
-                    } else {
-                        error!("No factions loaded...")
-                    }
-                }
+                ButtonAction::Play => next_menu_screen.set(MenuScreen::Lobby),
             },
             Interaction::Hovered | Interaction::None => {}
         }
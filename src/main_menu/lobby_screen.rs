@@ -0,0 +1,527 @@
+use bevy::prelude::*;
+
+use crate::game::teams::Team;
+use crate::load_game::load_factions::{FactionBlueprint, Factions};
+use crate::main_menu::{despawn_screen, MainMenuTag, MenuScreen};
+use crate::resources::{
+    AiDifficulty, Controller, GameMode, PlayerSettings, TeamSetting, TeamSettings,
+};
+use crate::styles::{HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON};
+use crate::AppState;
+
+/*
+The lobby is shown before a game starts. Here the player picks the faction and team to play,
+the faction and difficulty of the computer-controlled opponent, and the game mode.
+*/
+
+// --- Plugin ---
+
+pub struct LobbyScreenPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for LobbyScreenPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(self.state.clone()), spawn_ui)
+            .add_systems(OnExit(self.state.clone()), despawn_screen::<LobbyScreenTag>)
+            .add_systems(
+                Update,
+                (btn_interaction_handler, btn_action_handler).run_if(in_state(self.state.clone())),
+            );
+    }
+}
+
+// --- Constants ---
+
+const SELECTED_BORDER: Color = Color::GOLD;
+const NORMAL_BORDER: Color = Color::WHITE;
+
+/// The teams the player can choose to play as.
+const PLAYABLE_TEAMS: [Team; 2] = [Team::Red, Team::Blue];
+const AI_DIFFICULTIES: [AiDifficulty; 3] =
+    [AiDifficulty::Easy, AiDifficulty::Normal, AiDifficulty::Hard];
+const GAME_MODES: [GameMode; 3] = [GameMode::Classic, GameMode::HighBounty, GameMode::NoBounty];
+
+// --- Resources ---
+
+/// The choices made in the lobby, until the game is started.
+#[derive(Resource)]
+struct LobbySelection {
+    faction_index: usize,
+    opponent_faction_index: usize,
+    team: Team,
+    ai_difficulty: AiDifficulty,
+    game_mode: GameMode,
+}
+
+// --- Components ---
+
+#[derive(Component)]
+struct LobbyScreenTag;
+
+#[derive(PartialEq)]
+enum ButtonAction {
+    /// Select the faction with the given index in the `Factions` resource.
+    SelectFaction(usize),
+    /// Select the faction of the opponent with the given index in the `Factions` resource.
+    SelectOpponentFaction(usize),
+    SelectTeam(Team),
+    SelectAiDifficulty(AiDifficulty),
+    SelectGameMode(GameMode),
+    Start,
+    Back,
+}
+
+impl ButtonAction {
+    fn is_selected(&self, selection: &LobbySelection) -> bool {
+        match self {
+            ButtonAction::SelectFaction(index) => *index == selection.faction_index,
+            ButtonAction::SelectOpponentFaction(index) => {
+                *index == selection.opponent_faction_index
+            }
+            ButtonAction::SelectTeam(team) => *team == selection.team,
+            ButtonAction::SelectAiDifficulty(difficulty) => *difficulty == selection.ai_difficulty,
+            ButtonAction::SelectGameMode(game_mode) => *game_mode == selection.game_mode,
+            ButtonAction::Start | ButtonAction::Back => false,
+        }
+    }
+}
+
+#[derive(Component)]
+struct LobbyButton {
+    action: ButtonAction,
+}
+
+// --- Systems ---
+
+fn spawn_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    loaded_factions: Option<Res<Factions>>,
+) {
+    commands.insert_resource(LobbySelection {
+        faction_index: 0,
+        opponent_faction_index: 0,
+        team: Team::Red,
+        ai_difficulty: AiDifficulty::default(),
+        game_mode: GameMode::default(),
+    });
+
+    let font = asset_server.load("ui/fonts/warioland4tt/Warioland4chmc-VApe.ttf");
+    commands
+        .spawn((
+            MainMenuTag,
+            LobbyScreenTag,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.),
+                    ..Default::default()
+                },
+                background_color: BackgroundColor(Color::BLACK),
+                ..Default::default()
+            },
+        ))
+        .with_children(|builder| {
+            // Spawn title.
+            builder.spawn(
+                TextBundle::from_section(
+                    "Choose your faction",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 50.0,
+                        ..Default::default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::vertical(Val::Px(30.)),
+                    ..Default::default()
+                }),
+            );
+
+            // Spawn a button for each faction.
+            match &loaded_factions {
+                Some(factions) if !factions.0.is_empty() => {
+                    for (index, faction) in factions.0.iter().enumerate() {
+                        spawn_faction_btn(builder, index, faction, font.clone());
+                    }
+                }
+                _ => {
+                    builder.spawn(TextBundle::from_section(
+                        "No factions loaded...",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 30.0,
+                            color: Color::RED,
+                        },
+                    ));
+                }
+            }
+
+            // Spawn team selection.
+            builder.spawn(
+                TextBundle::from_section(
+                    "Choose your team",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 30.0,
+                        ..Default::default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(20.)),
+                    ..Default::default()
+                }),
+            );
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(12.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|team_row| {
+                    for team in PLAYABLE_TEAMS {
+                        spawn_text_btn(
+                            team_row,
+                            ButtonAction::SelectTeam(team),
+                            team.to_string(),
+                            team.get_color(),
+                            font.clone(),
+                        );
+                    }
+                });
+
+            // Spawn opponent faction selection.
+            builder.spawn(
+                TextBundle::from_section(
+                    "Choose your opponent",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 30.0,
+                        ..Default::default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(20.)),
+                    ..Default::default()
+                }),
+            );
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(12.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|opponent_row| {
+                    let Some(factions) = &loaded_factions else {
+                        return;
+                    };
+                    for (index, faction) in factions.0.iter().enumerate() {
+                        spawn_text_btn(
+                            opponent_row,
+                            ButtonAction::SelectOpponentFaction(index),
+                            faction.name.clone(),
+                            Color::WHITE,
+                            font.clone(),
+                        );
+                    }
+                });
+
+            // Spawn opponent difficulty selection.
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(12.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|difficulty_row| {
+                    for difficulty in AI_DIFFICULTIES {
+                        spawn_text_btn(
+                            difficulty_row,
+                            ButtonAction::SelectAiDifficulty(difficulty),
+                            difficulty.to_string(),
+                            Color::WHITE,
+                            font.clone(),
+                        );
+                    }
+                });
+
+            // Spawn game mode selection.
+            builder.spawn(
+                TextBundle::from_section(
+                    "Game mode",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 30.0,
+                        ..Default::default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(20.)),
+                    ..Default::default()
+                }),
+            );
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(12.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|game_mode_row| {
+                    for game_mode in GAME_MODES {
+                        spawn_text_btn(
+                            game_mode_row,
+                            ButtonAction::SelectGameMode(game_mode),
+                            game_mode.to_string(),
+                            Color::WHITE,
+                            font.clone(),
+                        );
+                    }
+                });
+
+            // Spawn navigation buttons.
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(12.),
+                        margin: UiRect::top(Val::Px(30.)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|nav_row| {
+                    spawn_text_btn(
+                        nav_row,
+                        ButtonAction::Back,
+                        "Back".to_string(),
+                        Color::WHITE,
+                        font.clone(),
+                    );
+                    spawn_text_btn(
+                        nav_row,
+                        ButtonAction::Start,
+                        "Start".to_string(),
+                        Color::WHITE,
+                        font.clone(),
+                    );
+                });
+        });
+}
+
+fn btn_action_handler(
+    mut commands: Commands,
+    query: Query<(&Interaction, &LobbyButton), Changed<Interaction>>,
+    mut selection: ResMut<LobbySelection>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+    loaded_factions: Option<Res<Factions>>,
+) {
+    for (interaction, lobby_button) in query.iter() {
+        match *interaction {
+            Interaction::Pressed => match lobby_button.action {
+                ButtonAction::SelectFaction(index) => selection.faction_index = index,
+                ButtonAction::SelectOpponentFaction(index) => {
+                    selection.opponent_faction_index = index
+                }
+                ButtonAction::SelectTeam(team) => selection.team = team,
+                ButtonAction::SelectAiDifficulty(difficulty) => {
+                    selection.ai_difficulty = difficulty
+                }
+                ButtonAction::SelectGameMode(game_mode) => selection.game_mode = game_mode,
+                ButtonAction::Start => {
+                    if let Some(factions) = &loaded_factions {
+                        if let (Some(selected_faction), Some(opponent_faction)) = (
+                            factions.0.get(selection.faction_index),
+                            factions.0.get(selection.opponent_faction_index),
+                        ) {
+                            commands.insert_resource(PlayerSettings {
+                                team: selection.team,
+                            });
+                            commands.insert_resource(selection.game_mode);
+                            commands.insert_resource(create_team_settings(
+                                &selection,
+                                selected_faction,
+                                opponent_faction,
+                            ));
+                            next_state.set(AppState::Game)
+                        } else {
+                            error!("Couldn't get the selected factions from loaded factions.")
+                        }
+                    } else {
+                        error!("No factions loaded...")
+                    }
+                }
+                ButtonAction::Back => next_menu_screen.set(MenuScreen::Title),
+            },
+            Interaction::Hovered | Interaction::None => {}
+        }
+    }
+}
+
+/// Runs every frame, as the selection can change without the button being interacted with.
+fn btn_interaction_handler(
+    mut query: Query<(
+        &Interaction,
+        &LobbyButton,
+        &mut BackgroundColor,
+        &mut BorderColor,
+    )>,
+    selection: Res<LobbySelection>,
+) {
+    for (interaction, lobby_button, mut bg_colour, mut border_colour) in query.iter_mut() {
+        *bg_colour = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+        *border_colour = if lobby_button.action.is_selected(&selection) {
+            SELECTED_BORDER.into()
+        } else {
+            NORMAL_BORDER.into()
+        };
+    }
+}
+
+// --- Helper functions ---
+
+/// The player controls the selected team, while the remaining teams are given to the computer.
+fn create_team_settings(
+    selection: &LobbySelection,
+    selected_faction: &FactionBlueprint,
+    opponent_faction: &FactionBlueprint,
+) -> TeamSettings {
+    TeamSettings(
+        PLAYABLE_TEAMS
+            .iter()
+            .map(|team| {
+                let team_setting = if *team == selection.team {
+                    TeamSetting {
+                        faction: selected_faction.clone(),
+                        controller: Controller::Human,
+                        color: team.get_color(),
+                    }
+                } else {
+                    TeamSetting {
+                        faction: opponent_faction.clone(),
+                        controller: Controller::Ai(selection.ai_difficulty),
+                        color: team.get_color(),
+                    }
+                };
+                (*team, team_setting)
+            })
+            .collect(),
+    )
+}
+
+fn spawn_text_btn(
+    builder: &mut ChildBuilder,
+    action: ButtonAction,
+    text: String,
+    text_color: Color,
+    font: Handle<Font>,
+) {
+    builder
+        .spawn((
+            LobbyButton { action },
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(200.),
+                    border: UiRect::all(Val::Px(3.)),
+                    // horizontally center child text
+                    justify_content: JustifyContent::Center,
+                    // vertically center child text
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                border_color: BorderColor(NORMAL_BORDER),
+                background_color: BackgroundColor(NORMAL_BUTTON),
+                ..Default::default()
+            },
+        ))
+        .with_children(|btn_builder| {
+            btn_builder.spawn(TextBundle::from_section(
+                text,
+                TextStyle {
+                    font,
+                    font_size: 30.0,
+                    color: text_color,
+                },
+            ));
+        });
+}
+
+/// Spawns a button showing the faction name and the icons of all its buildings.
+fn spawn_faction_btn(
+    builder: &mut ChildBuilder,
+    index: usize,
+    faction: &FactionBlueprint,
+    font: Handle<Font>,
+) {
+    builder
+        .spawn((
+            LobbyButton {
+                action: ButtonAction::SelectFaction(index),
+            },
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(500.),
+                    border: UiRect::all(Val::Px(3.)),
+                    padding: UiRect::all(Val::Px(8.)),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.),
+                    ..Default::default()
+                },
+                border_color: BorderColor(NORMAL_BORDER),
+                background_color: BackgroundColor(NORMAL_BUTTON),
+                ..Default::default()
+            },
+        ))
+        .with_children(|btn_builder| {
+            btn_builder.spawn(TextBundle::from_section(
+                faction.name.clone(),
+                TextStyle {
+                    font,
+                    font_size: 30.0,
+                    color: Color::WHITE,
+                },
+            ));
+            btn_builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(8.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|icon_row| {
+                    for building in faction.buildings.values() {
+                        icon_row.spawn(ImageBundle {
+                            image: UiImage::new(building.icon.clone()),
+                            style: Style {
+                                width: Val::Px(48.),
+                                height: Val::Px(48.),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        });
+                    }
+                });
+        });
+}
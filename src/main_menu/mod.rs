@@ -1,33 +1,66 @@
 use bevy::prelude::*;
 
 use crate::main_menu::init_screen::InitScreenPlugin;
+use crate::main_menu::lobby_screen::LobbyScreenPlugin;
 use crate::AppState;
 
 mod init_screen;
+mod lobby_screen;
 
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(InitScreenPlugin {
-            state: AppState::MainMenu,
-        })
+        app.add_plugins((
+            InitScreenPlugin {
+                state: MenuScreen::Title,
+            },
+            LobbyScreenPlugin {
+                state: MenuScreen::Lobby,
+            },
+        ))
+        .init_state::<MenuScreen>()
         .add_systems(OnEnter(AppState::MainMenu), main_menu)
         .add_systems(OnExit(AppState::MainMenu), main_menu_cleanup);
     }
 }
 
+// States - Related to the main menu
+
+/// The screen currently shown in the main menu.
+/// Is `None` whenever the app is not in the main menu.
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum MenuScreen {
+    #[default]
+    None,
+    Title,
+    Lobby,
+}
+
 // --- Components ---
 
 #[derive(Component)]
 pub struct MainMenuTag;
 
-pub fn main_menu() {
-    println!("You are on the main menu");
+pub fn main_menu(mut next_menu_screen: ResMut<NextState<MenuScreen>>) {
+    info!("You are on the main menu");
+    next_menu_screen.set(MenuScreen::Title);
 }
 
-pub fn main_menu_cleanup(mut commands: Commands, query: Query<Entity, With<MainMenuTag>>) {
+pub fn main_menu_cleanup(
+    mut commands: Commands,
+    query: Query<Entity, With<MainMenuTag>>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+) {
     for main_menu_entity in query.iter() {
         commands.entity(main_menu_entity).despawn_recursive();
     }
+    next_menu_screen.set(MenuScreen::None);
+}
+
+/// Despawns every entity with the given screen tag. Used when leaving a menu screen.
+pub fn despawn_screen<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for screen_entity in query.iter() {
+        commands.entity(screen_entity).despawn_recursive();
+    }
 }
@@ -1,10 +1,94 @@
+use std::fmt;
+use std::fmt::Formatter;
+
 use crate::game::teams::Team;
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 
 use crate::load_game::load_factions::FactionBlueprint;
 
 #[derive(Resource)]
 pub struct PlayerSettings {
     pub team: Team,
+}
+
+/// The rules of the match.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default, Reflect)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    /// Kills are worth twice as much, to reward aggressive play.
+    HighBounty,
+    /// Gold only comes from income.
+    NoBounty,
+}
+
+impl GameMode {
+    /// Scales the bounty of everything killed.
+    pub fn bounty_multiplier(&self) -> f32 {
+        match self {
+            GameMode::Classic => 1.,
+            GameMode::HighBounty => 2.,
+            GameMode::NoBounty => 0.,
+        }
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GameMode::Classic => write!(f, "Classic"),
+            GameMode::HighBounty => write!(f, "High bounty"),
+            GameMode::NoBounty => write!(f, "No bounty"),
+        }
+    }
+}
+
+/// Who is giving the orders for a team.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+pub enum Controller {
+    Human,
+    Ai(AiDifficulty),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Reflect)]
+pub enum AiDifficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl fmt::Display for AiDifficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AiDifficulty::Easy => write!(f, "Easy"),
+            AiDifficulty::Normal => write!(f, "Normal"),
+            AiDifficulty::Hard => write!(f, "Hard"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct TeamSetting {
     pub faction: FactionBlueprint,
+    pub controller: Controller,
+    pub color: Color,
+}
+
+/// The settings of every team taking part in the match.
+#[derive(Resource, Default, Reflect)]
+pub struct TeamSettings(pub HashMap<Team, TeamSetting>);
+
+impl TeamSettings {
+    pub fn faction(&self, team: Team) -> Option<&FactionBlueprint> {
+        self.0.get(&team).map(|team_setting| &team_setting.faction)
+    }
+
+    /// Falls back to the default team color, if the team has no settings.
+    pub fn color(&self, team: Team) -> Color {
+        self.0
+            .get(&team)
+            .map_or(team.get_color(), |team_setting| team_setting.color)
+    }
 }
@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+/*
+Colors shared by the menus and screens, so the buttons look the same everywhere.
+*/
+
+// --- Constants ---
+
+pub const NORMAL_BUTTON: Color = Color::rgba(1., 1., 1., 0.2);
+pub const HOVERED_BUTTON: Color = Color::rgba(1., 1., 1., 0.3);
+pub const PRESSED_BUTTON: Color = Color::rgba(1., 1., 1., 0.5);